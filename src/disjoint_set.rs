@@ -0,0 +1,101 @@
+// disjoint-set forest (union-find) over the elements 0..len. union by rank and path halving keep
+// find and union close to constant time
+#[derive(Debug, Clone, Default)]
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    set_count: usize,
+}
+
+impl DisjointSet {
+    // len elements, each in a set of its own
+    pub fn new(len: usize) -> Self {
+        DisjointSet {
+            parent: (0..len).collect(),
+            rank: vec![0; len],
+            set_count: len,
+        }
+    }
+
+    // number of elements
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    // number of disjoint sets
+    pub fn set_count(&self) -> usize {
+        self.set_count
+    }
+
+    // adds a new element in a set of its own and returns it
+    pub fn push(&mut self) -> usize {
+        let x = self.parent.len();
+        self.parent.push(x);
+        self.rank.push(0);
+        self.set_count += 1;
+        x
+    }
+
+    // representative of the set holding x. panics if x is out of range
+    pub fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    // merges the sets holding a and b. returns false if they were already the same set
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        let (root, child) = if self.rank[a] < self.rank[b] { (b, a) } else { (a, b) };
+        self.parent[child] = root;
+        if self.rank[root] == self.rank[child] {
+            self.rank[root] += 1;
+        }
+        self.set_count -= 1;
+        true
+    }
+
+    // true if a and b are in the same set
+    pub fn same_set(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DisjointSet;
+
+    #[test]
+    fn union_find() {
+        let mut sets = DisjointSet::new(6);
+        assert_eq!(sets.len(), 6);
+        assert_eq!(sets.set_count(), 6);
+        assert!(!sets.same_set(0, 1));
+
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert_eq!(sets.set_count(), 3);
+        assert!(sets.same_set(0, 3));
+        assert!(!sets.same_set(0, 4));
+        assert_eq!(sets.find(2), sets.find(1));
+
+        let x = sets.push();
+        assert_eq!(x, 6);
+        assert_eq!(sets.set_count(), 4);
+        assert!(sets.union(x, 5));
+        assert!(sets.same_set(5, 6));
+        assert!(DisjointSet::default().is_empty());
+    }
+}
@@ -1,336 +1,452 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{HashMap, HashSet};
 use std::default::Default;
-use std::fmt;
-
-#[derive(Debug, PartialEq, Clone)]
-struct Vertex<T> {
-    id: String,
-    value: T,
-}
-
-impl<T> fmt::Display for Vertex<T>
-where
-    T: fmt::Display,
-{
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "({}, {})", self.id, self.value)
-    }
+use std::hash::Hash;
+
+mod all_pairs;
+mod components;
+mod display;
+mod dot;
+mod edge;
+mod error;
+mod flow;
+mod frozen;
+mod multigraph;
+mod shortest_path;
+mod snapshot;
+mod spanning;
+mod subgraph;
+#[cfg(test)]
+mod test_support;
+mod text;
+mod topological;
+mod traversal;
+mod vertex;
+mod view;
+mod weight;
+
+pub use self::all_pairs::DistanceMatrix;
+pub use self::components::Components;
+pub use self::display::GraphDisplay;
+pub use self::dot::{DotError, DotValue};
+pub use self::edge::{Edge, WeightedBy};
+pub use self::error::GraphError;
+pub use self::flow::MaxFlow;
+pub use self::frozen::FrozenGraph;
+pub use self::multigraph::{EdgeId, MultiEdge, MultiGraph};
+pub use self::shortest_path::{NegativeCycle, Path, ShortestPathTree};
+pub use self::snapshot::{Codec, SnapshotError};
+pub use self::spanning::SpanningForest;
+pub use self::subgraph::Filtered;
+pub use self::text::ReadError;
+pub use self::topological::CycleError;
+pub use self::traversal::{Control, Visitor};
+pub use self::vertex::VertexId;
+pub use self::view::GraphView;
+pub use self::weight::Weight;
+
+use self::edge::Adjacent;
+use self::vertex::VertexTable;
+
+// adjacency list implementation. edge weights are i32 unless another Weight type is given, and vertices are
+// identified by String keys unless another Hash + Eq key type is given. every edge also carries a payload of type E,
+// () unless another type is given, see Graph::add_edge_with.
+// every vertex is also given a VertexId handle when it is added. the graph is stored by handle, so the key is only
+// hashed once per call, and the *_by_id methods skip that lookup entirely.
+// graphs are directed unless created with new_undirected
+#[derive(Debug)]
+pub struct Graph<T, W = i32, K = String, E = ()> {
+    verts: VertexTable<K, T>,                                // vertex key to handle and handle to vertex. vertex stores information such as a payload
+    adjacency_lists: Vec<HashMap<VertexId, Adjacent<W, E>>>, // handle to the adjacent vertices and the edge to each
+    in_adjacency_lists: Vec<HashSet<VertexId>>,              // handle to the vertices with an edge into it. empty when undirected
+    edge_count: usize,
+    directed: bool,
 }
 
-impl<T> Vertex<T>
+impl<T, W, K, E> Graph<T, W, K, E>
 where
     T: Default + Clone,
+    W: Weight,
+    K: Hash + Eq + Clone,
+    E: Default + Clone,
 {
-    fn new(id: &String, value: &T) -> Self {
-        Vertex {
-            id: id.clone(),
-            value: value.clone(),
+    pub fn new() -> Graph<T, W, K, E> {
+        Graph {
+            verts: VertexTable::new(),
+            adjacency_lists: Vec::new(),
+            in_adjacency_lists: Vec::new(),
+            edge_count: 0,
+            directed: true,
         }
     }
-}
-
-use i32 as Weight;
-
-#[derive(Debug, Clone)]
-struct Edge {
-    src: String,
-    dst: String,
-    weight: Weight,
-}
 
-impl Edge {
-    fn new(src: &String, dst: &String) -> Self {
-        Edge {
-            src: src.clone(),
-            dst: dst.clone(),
-            weight: 1,
-        }
+    // a graph where every edge goes both ways. x-y is in the adjacency lists of both x and y,
+    // and is added, removed and counted as a single edge
+    pub fn new_undirected() -> Graph<T, W, K, E> {
+        Graph { directed: false, ..Graph::new() }
     }
-    fn with_weight(mut self, weight: Weight) -> Self {
-        self.weight = weight;
-        self
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
     }
-    fn contains(&self, vert_id: &String) -> bool {
-        self.src.eq(vert_id) || self.dst.eq(vert_id)
+
+    pub fn vertex_count(&self) -> usize {
+        self.verts.len()
     }
-}
 
-impl std::cmp::PartialEq for Edge {
-    fn eq(&self, rhs: &Edge) -> bool {
-        format!("{}{}", self.src, self.dst) == format!("{}{}", rhs.src, rhs.dst)
+    // number of edges, an undirected edge counts once
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
     }
-    fn ne(&self, rhs: &Edge) -> bool {
-        format!("{}{}", self.src, self.dst) != format!("{}{}", rhs.src, rhs.dst)
+
+    // adds the vertex x, if it is not there
+    pub fn add_vertex(&mut self, id: &K, value: &T) -> bool {
+        let (_, added) = self.insert_vertex(id, value);
+        added
     }
-}
 
-impl std::cmp::Eq for Edge {}
+    // adds the vertex x if it is not there, and returns its handle either way.
+    // the value of a vertex that is already there is left alone
+    pub fn intern_vertex(&mut self, id: &K, value: &T) -> VertexId {
+        self.insert_vertex(id, value).0
+    }
 
-impl std::hash::Hash for Edge {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        format!("{}{}", self.src, self.dst).hash(state);
+    // handle of the vertex x
+    pub fn vertex_id(&self, id: &K) -> Option<VertexId> {
+        self.verts.id(id)
     }
-}
 
-// adjacency list implementation
-#[derive(Debug)]
-pub struct Graph<T> {
-    verts: HashMap<String, Vertex<T>>, // vertex id to vertex. vertex stores information such as a payload
-    edges: HashSet<Edge>,              // set of edges in the graph
-    adjacency_lists: HashMap<String, HashSet<String>>, // vertex id to list of adjacent vertices by vertex id
-}
+    // key of the vertex with handle v
+    pub fn vertex_key(&self, v: VertexId) -> Option<&K> {
+        self.verts.get(v).map(|vertex| &vertex.id)
+    }
 
-impl<T> Graph<T>
-where
-    T: Default + Clone,
-{
-    pub fn new() -> Graph<T> {
-        Graph {
-            verts: HashMap::new(),
-            edges: HashSet::new(),
-            adjacency_lists: HashMap::new(),
-        }
+    // removes the vertex x and all of its edges
+    pub fn remove_vertex(&mut self, id: &K) -> Option<T> {
+        self.remove_vertex_by_id(self.vertex_id(id)?)
     }
 
-    // adds the vertex x, if it is not there
-    pub fn add_vertex(&mut self, id: &String, value: &T) -> bool {
-        let v = Vertex::new(id, value);
-        if let None = self.verts.get(&v.id) {
-            self.verts.insert(v.id.clone(), v);
-            true
+    // O(degree of the vertex)
+    pub fn remove_vertex_by_id(&mut self, v: VertexId) -> Option<T> {
+        let vertex = self.verts.remove(v)?;
+        let adjacency_list = std::mem::take(&mut self.adjacency_lists[v.index()]);
+        self.edge_count -= adjacency_list.len();
+
+        if self.directed {
+            let in_adjacency_list = std::mem::take(&mut self.in_adjacency_lists[v.index()]);
+            for u in adjacency_list.keys() {
+                self.in_adjacency_lists[u.index()].remove(&v);
+            }
+            // a self loop was already counted with the out edges
+            for u in in_adjacency_list.into_iter().filter(|&u| u != v) {
+                self.adjacency_lists[u.index()].remove(&v);
+                self.edge_count -= 1;
+            }
         } else {
-            false
+            for u in adjacency_list.keys() {
+                self.adjacency_lists[u.index()].remove(&v);
+            }
         }
+        Some(vertex.value)
     }
 
-    // removes the vertex x and all of its edges
-    pub fn remove_vertex(&mut self, id: &String) -> Option<T> {
-        let mut edges_to_remove: Vec<Edge> = Vec::new();
-        for e in &self.edges {
-            if e.contains(id) {
-                edges_to_remove.push(e.clone());
-            }
-        }
-        for e in edges_to_remove {
-            self.edges.remove(&e);
+    // dds the edge from the vertex x to the vertex y, if it is not there;
+    // an edge weighing W::infinity() is kept, but the shortest path searches can't use it
+    pub fn add_weighted_edge(&mut self, src: &K, dst: &K, weight: W) -> bool {
+        match (self.vertex_id(src), self.vertex_id(dst)) {
+            (Some(src), Some(dst)) => self.add_weighted_edge_by_id(src, dst, weight),
+            _ => false,
         }
+    }
 
-        let result = self.verts.remove(id);
-        self.adjacency_lists.remove(id);
-
-        for (_, adjacency_list) in &mut self.adjacency_lists {
-            adjacency_list.remove(id);
-        }
+    pub fn add_weighted_edge_by_id(&mut self, src: VertexId, dst: VertexId, weight: W) -> bool {
+        self.add_edge_with_by_id(src, dst, weight, E::default())
+    }
 
-        if let Some(vertex) = result {
-            return Some(vertex.value);
+    // adds the edge src->dst carrying payload, if it is not there. the plain add_*edge methods give the
+    // edge E::default()
+    pub fn add_edge_with(&mut self, src: &K, dst: &K, weight: W, payload: E) -> bool {
+        match (self.vertex_id(src), self.vertex_id(dst)) {
+            (Some(src), Some(dst)) => self.add_edge_with_by_id(src, dst, weight, payload),
+            _ => false,
         }
-        None
     }
 
-    // dds the edge from the vertex x to the vertex y, if it is not there;
-    pub fn add_weighted_edge(&mut self, src: &String, dst: &String, weight: Weight) -> bool {
-        if let None = self.verts.get(src) {
+    pub fn add_edge_with_by_id(&mut self, src: VertexId, dst: VertexId, weight: W, payload: E) -> bool {
+        if !self.verts.contains(src) || !self.verts.contains(dst) {
             return false;
         }
-
-        if let None = self.verts.get(dst) {
+        if self.adjacency_lists[src.index()].contains_key(&dst) {
             return false;
         }
 
-        if !self.edges.insert(Edge::new(src, dst).with_weight(weight)) {
-            return false;
+        let (mut payload, mut mirror) = (Some(payload), None);
+        if self.directed {
+            self.in_adjacency_lists[dst.index()].insert(src);
+        } else if dst != src {
+            // the payload of an undirected edge is kept at its smaller end only
+            if dst < src {
+                std::mem::swap(&mut payload, &mut mirror);
+            }
+            self.adjacency_lists[dst.index()].insert(src, Adjacent { weight, payload: mirror });
         }
+        self.adjacency_lists[src.index()].insert(dst, Adjacent { weight, payload });
+        self.edge_count += 1;
+        true
+    }
 
-        if let Some(adjacency_list) = self.adjacency_lists.get_mut(src) {
-            adjacency_list.insert(dst.clone())
-        } else {
-            let mut adjacency_list = HashSet::new();
-            let result = adjacency_list.insert(dst.clone());
-            self.adjacency_lists.insert(src.clone(), adjacency_list);
-            result
-        }
+    // adds an edge with a weight of W::one()
+    pub fn add_edge(&mut self, src: &K, dst: &K) -> bool {
+        self.add_weighted_edge(src, dst, W::one())
     }
 
-    pub fn add_edge(&mut self, src: &String, dst: &String) -> bool {
-        self.add_weighted_edge(src, dst, 1)
+    pub fn add_edge_by_id(&mut self, src: VertexId, dst: VertexId) -> bool {
+        self.add_weighted_edge_by_id(src, dst, W::one())
     }
 
     // removes the edge from the vertex x to the vertex y, if it is there
-    pub fn remove_edge(&mut self, src: &String, dst: &String) -> bool {
-        self.edges.remove(&Edge::new(src, dst));
+    pub fn remove_edge(&mut self, src: &K, dst: &K) -> bool {
+        match (self.vertex_id(src), self.vertex_id(dst)) {
+            (Some(src), Some(dst)) => self.remove_edge_by_id(src, dst),
+            _ => false,
+        }
+    }
+
+    pub fn remove_edge_by_id(&mut self, src: VertexId, dst: VertexId) -> bool {
+        self.take_edge_by_id(src, dst).is_some()
+    }
+
+    // removes the edge src->dst and returns its weight and payload
+    pub fn take_edge(&mut self, src: &K, dst: &K) -> Option<(W, E)> {
+        self.take_edge_by_id(self.vertex_id(src)?, self.vertex_id(dst)?)
+    }
 
-        if let Some(adjacency_list) = self.adjacency_lists.get_mut(src) {
-            return adjacency_list.remove(dst);
+    pub fn take_edge_by_id(&mut self, src: VertexId, dst: VertexId) -> Option<(W, E)> {
+        let edge = self.adjacency_lists.get_mut(src.index())?.remove(&dst)?;
+        let mut payload = edge.payload;
+        if self.directed {
+            self.in_adjacency_lists[dst.index()].remove(&src);
+        } else if let Some(mirror) = self.adjacency_lists[dst.index()].remove(&src) {
+            payload = payload.or(mirror.payload);
         }
-        false
+        self.edge_count -= 1;
+        Some((edge.weight, payload.unwrap()))
     }
 
     // lists all vertices y such that there is an edge from the vertex x to the vertex y
-    pub fn get_adjacent_verts(&self, id: &String) -> Option<HashSet<String>> {
-        if let Some(adjacency_list) = self.adjacency_lists.get(id) {
-            return Some(adjacency_list.clone());
+    pub fn get_adjacent_verts(&self, id: &K) -> Option<HashSet<K>> {
+        let v = self.vertex_id(id)?;
+        Some(self.adjacent_ids(v).map(|u| self.verts.key(u).clone()).collect())
+    }
+
+    // handles of all vertices y such that there is an edge from the vertex with handle v to y.
+    // empty if v is not in the graph
+    pub fn adjacent_ids(&self, v: VertexId) -> impl Iterator<Item = VertexId> + '_ {
+        self.out_edges(v).map(|(u, _)| u)
+    }
+
+    // handles of all vertices y such that there is an edge from y to the vertex with handle v.
+    // empty if v is not in the graph
+    pub fn in_adjacent_ids(&self, v: VertexId) -> impl Iterator<Item = VertexId> + '_ {
+        // an undirected edge is in the adjacency lists of both vertices, so they double as the in adjacency lists
+        let incoming = self.directed.then(|| self.in_adjacency_lists.get(v.index())).flatten().into_iter().flatten().copied();
+        let undirected = (!self.directed).then(|| self.adjacent_ids(v)).into_iter().flatten();
+        incoming.chain(undirected)
+    }
+
+    // every vertex with its value, in the order they were added
+    pub fn vertices(&self) -> impl Iterator<Item = (&K, &T)> {
+        self.verts.iter().map(|(_, vertex)| (&vertex.id, &vertex.value))
+    }
+
+    // every edge as (src, dst, weight). an undirected edge is given once, from the vertex that was added first
+    pub fn edges(&self) -> impl Iterator<Item = (&K, &K, W)> {
+        let directed = self.directed;
+        self.arcs()
+            .filter(move |(src, dst, _)| directed || src <= dst)
+            .map(|(src, dst, w)| (self.verts.key(src), self.verts.key(dst), w))
+    }
+
+    // vertices y such that there is an edge from the vertex x to y. empty if x is not in the graph
+    pub fn neighbors<'a>(&'a self, id: &K) -> impl Iterator<Item = &'a K> + 'a {
+        let v = self.vertex_id(id);
+        v.into_iter().flat_map(|v| self.adjacent_ids(v)).map(|u| self.verts.key(u))
+    }
+
+    // vertices y such that there is an edge from y to the vertex x. empty if x is not in the graph.
+    // for an undirected graph these are the same as neighbors
+    pub fn in_neighbors<'a>(&'a self, id: &K) -> impl Iterator<Item = &'a K> + 'a {
+        let v = self.vertex_id(id);
+        v.into_iter().flat_map(|v| self.in_adjacent_ids(v)).map(|u| self.verts.key(u))
+    }
+
+    // number of edges out of the vertex x
+    pub fn out_degree(&self, id: &K) -> Option<usize> {
+        Some(self.adjacency_lists[self.vertex_id(id)?.index()].len())
+    }
+
+    // number of edges into the vertex x
+    pub fn in_degree(&self, id: &K) -> Option<usize> {
+        let v = self.vertex_id(id)?;
+        if self.directed {
+            Some(self.in_adjacency_lists[v.index()].len())
+        } else {
+            Some(self.adjacency_lists[v.index()].len())
         }
-        None
     }
 
-    // tests whether there is an edge from the vertex x to the vertex y
-    pub fn is_adjacent(&self, src: &String, dst: &String) -> bool {
-        if let Some(adjacency_list) = self.adjacency_lists.get(src) {
-            for adjacent_vert in adjacency_list {
-                if adjacent_vert == dst {
-                    return true;
+    // a copy of the graph with every edge reversed. an undirected graph is its own transpose
+    pub fn transpose(&self) -> Graph<T, W, K, E> {
+        let mut adjacency_lists: Vec<HashMap<VertexId, Adjacent<W, E>>> = vec![HashMap::new(); self.adjacency_lists.len()];
+        let mut in_adjacency_lists: Vec<HashSet<VertexId>> = vec![HashSet::new(); self.in_adjacency_lists.len()];
+        if self.directed {
+            for (src, adjacency_list) in self.adjacency_lists.iter().enumerate() {
+                let src = VertexId::new(src);
+                for (&dst, edge) in adjacency_list {
+                    adjacency_lists[dst.index()].insert(src, edge.clone());
+                    in_adjacency_lists[src.index()].insert(dst);
                 }
             }
+        } else {
+            // the payload of an undirected edge stays at its smaller end
+            adjacency_lists.clone_from(&self.adjacency_lists);
+        }
+
+        Graph {
+            verts: self.verts.clone(),
+            adjacency_lists,
+            in_adjacency_lists,
+            edge_count: self.edge_count,
+            directed: self.directed,
         }
-        false
+    }
+
+    // tests whether there is an edge from the vertex x to the vertex y
+    pub fn is_adjacent(&self, src: &K, dst: &K) -> bool {
+        self.has_edge(src, dst)
     }
 
     //  returns the value associated with the vertex x;
-    pub fn get_value(&self, id: &String) -> Option<T> {
-        if let Some(v) = self.verts.get(id) {
-            Some(v.value.clone())
-        } else {
-            None
-        }
+    pub fn get_value(&self, id: &K) -> Option<T> {
+        self.value(self.vertex_id(id)?).cloned()
+    }
+
+    // the value of the vertex with handle v, without cloning it
+    pub fn value(&self, v: VertexId) -> Option<&T> {
+        self.verts.get(v).map(|vertex| &vertex.value)
+    }
+
+    pub fn value_mut(&mut self, v: VertexId) -> Option<&mut T> {
+        self.verts.get_mut(v).map(|vertex| &mut vertex.value)
     }
 
     // sets the value associated with the vertex x to v.
-    pub fn set_value(&mut self, id: &String, value: &T) -> Option<T> {
-        if let Some(v) = self.verts.get_mut(id) {
-            let previous = v.value.clone();
-            v.value = value.clone();
-            Some(previous)
-        } else {
-            None
-        }
+    pub fn set_value(&mut self, id: &K, value: &T) -> Option<T> {
+        let v = self.value_mut(self.vertex_id(id)?)?;
+        Some(std::mem::replace(v, value.clone()))
     }
 
     // checks if the edge src->dst exists
-    pub fn has_edge(&self, src: &String, dst: &String) -> bool {
-        if let Some(_) = self.edges.get(&Edge::new(src, dst)) {
-            true
-        } else {
-            false
-        }
+    pub fn has_edge(&self, src: &K, dst: &K) -> bool {
+        self.edge_weight(src, dst).is_some()
     }
 
-    // 'dst' could be some other criteria
-    // breadth first search.
-    // as it stands this function is useless but can be modified to find a given vertex in the graph
-    pub fn search(&self, src: &String, dst: &String) -> Option<String> {
-        let mut queue: VecDeque<String> = VecDeque::new();
-        let mut marked_verts: HashSet<String> = HashSet::new();
-        queue.push_back(src.clone());
-        marked_verts.insert(src.clone());
+    pub fn has_edge_by_id(&self, src: VertexId, dst: VertexId) -> bool {
+        self.edge_weight_by_id(src, dst).is_some()
+    }
 
-        while queue.len() > 0 {
-            let w = queue.pop_front().unwrap();
+    // dst if it can be reached from src, found by breadth first search. see search_by for other criteria
+    pub fn search(&self, src: &K, dst: &K) -> Option<K> {
+        GraphView::search(self, src, dst)
+    }
 
-            if w.eq(dst) {
-                return Some(w);
-            }
+    // shortest path between two vertices as a list of vertex ids, empty if there is no path.
+    // see find_path for the cost of the path and shortest_path_tree for the paths to every other vertex
+    pub fn shortest_path(&self, src: &K, dst: &K) -> Vec<K> {
+        GraphView::shortest_path(self, src, dst)
+    }
 
-            if let Some(a) = self.get_adjacent_verts(&w) {
-                for x in &a {
-                    if !marked_verts.contains(x) {
-                        marked_verts.insert(x.clone());
-                        queue.push_back(x.clone());
-                    }
-                }
-            }
-        }
-        None
+    pub fn shortest_path_by_id(&self, src: VertexId, dst: VertexId) -> Vec<VertexId> {
+        GraphView::shortest_path_by_id(self, src, dst)
     }
 
-    // implementation of dijkstra's algorithm to find the shortest path between two vertices
-    pub fn shortest_path(&self, src: &String, dst: &String) -> Vec<String> {
-        if let None = self.verts.get(src) {
-            return vec![];
+    fn insert_vertex(&mut self, id: &K, value: &T) -> (VertexId, bool) {
+        let (v, added) = self.verts.insert(id, value);
+        if added {
+            self.adjacency_lists.push(HashMap::new());
+            self.in_adjacency_lists.push(HashSet::new());
         }
+        (v, added)
+    }
 
-        if let None = self.verts.get(dst) {
-            return vec![];
-        }
+    // weight of the edge src->dst, if it exists
+    fn edge_weight(&self, src: &K, dst: &K) -> Option<W> {
+        self.edge_weight_by_id(self.vertex_id(src)?, self.vertex_id(dst)?)
+    }
 
-        // dist[u] is the current distance from the source to vertex u
-        let mut dist: HashMap<String, Weight> = HashMap::new();
+    // every edge as (src, dst, weight). undirected edges are given in both directions
+    fn arcs(&self) -> impl Iterator<Item = (VertexId, VertexId, W)> + '_ {
+        view::arcs(self)
+    }
 
-        // prev contains pointers to previous hop nodes on the shortest path
-        //   or the next hp on the path from the given vertex to the source
-        let mut prev: HashMap<String, String> = HashMap::new();
+    // every edge ordered by source then destination, for output that doesn't depend on HashMap order.
+    // undirected edges are given once, from the smaller VertexId
+    fn sorted_edges(&self) -> Vec<(VertexId, VertexId, W)> {
+        let mut edges: Vec<(VertexId, VertexId, W)> = self.arcs().filter(|(src, dst, _)| self.directed || src <= dst).collect();
+        edges.sort_by_key(|&(src, dst, _)| (src, dst));
+        edges
+    }
+}
 
-        // Q is a set of vertices that has the least dist[u] value
-        let mut q: Vec<String> = Vec::new();
+impl<T, W, K, E> GraphView for Graph<T, W, K, E>
+where
+    T: Default + Clone,
+    W: Weight,
+    K: Hash + Eq + Clone,
+    E: Default + Clone,
+{
+    type Key = K;
+    type Value = T;
+    type Weight = W;
 
-        for (v, _) in &self.verts {
-            dist.entry(v.clone()).or_insert(Weight::MAX);
-            prev.entry(v.clone()).or_insert("".to_string());
-            q.push(v.clone());
-        }
-        *dist.entry(src.clone()).or_insert(Weight::MAX) = 0;
-
-        while q.len() > 0 {
-            // vertex in Q with min dist[u]
-            let mut u: String = q.get(0).unwrap().clone();
-            let mut min: Weight = *dist.get(&u).unwrap();
-            let mut rmpos = 0;
-            for (pos, v) in q.iter().enumerate() {
-                let v_min = *dist.get(v).unwrap();
-                if v_min < min {
-                    u = v.clone();
-                    min = v_min;
-                    rmpos = pos;
-                }
-            }
+    fn is_directed(&self) -> bool {
+        self.directed
+    }
 
-            if u.eq(dst) || min == Weight::MAX {
-                break;
-            }
+    fn vertex_count(&self) -> usize {
+        self.verts.len()
+    }
 
-            q.remove(rmpos);
+    fn edge_count(&self) -> usize {
+        self.edge_count
+    }
 
-            for v in &q {
-                if !self.is_adjacent(&u, v) {
-                    continue;
-                }
-                let w = self.edges.get(&Edge::new(&u, v)).unwrap().weight;
-                let alt: Weight = dist.get(&u).unwrap() + w;
-                if alt < *dist.get(v).unwrap() {
-                    *dist.entry(v.clone()).or_insert(Weight::MAX) = alt;
-                    *prev.entry(v.clone()).or_insert("".to_string()) = u.clone();
-                }
-            }
-        }
+    fn id_bound(&self) -> usize {
+        self.verts.slots()
+    }
 
-        let mut s: Vec<String> = Vec::new();
-        let mut u: String = dst.clone();
-        if prev.get(&u).unwrap().ne("") || u.eq(src) {
-            while u.ne("") {
-                s.push(u.clone());
-                u = prev.get(&u).unwrap().clone();
-            }
-        }
+    fn vertex_id(&self, id: &K) -> Option<VertexId> {
+        self.verts.id(id)
+    }
 
-        s.reverse();
+    fn vertex_key(&self, v: VertexId) -> Option<&K> {
+        self.verts.get(v).map(|vertex| &vertex.id)
+    }
 
-        return s;
+    fn value(&self, v: VertexId) -> Option<&T> {
+        self.verts.get(v).map(|vertex| &vertex.value)
     }
-}
 
-impl<T> fmt::Display for Graph<T> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut s = String::new();
-        for (vert, edges) in &self.adjacency_lists {
-            for (idx, conn) in edges.iter().enumerate() {
-                s += format!("{}->{}", vert, conn).as_str();
-                if idx < edges.len() - 1 {
-                    s += ","
-                }
-            }
-            s += ";"
-        }
-        write!(f, "{}", s)
+    fn vertex_ids(&self) -> impl Iterator<Item = VertexId> + '_ {
+        self.verts.ids()
+    }
+
+    // every edge out of v with its weight, without cloning the adjacency list
+    fn out_edges(&self, v: VertexId) -> impl Iterator<Item = (VertexId, W)> + '_ {
+        self.adjacency_lists.get(v.index()).into_iter().flatten().map(|(&u, edge)| (u, edge.weight))
+    }
+
+    fn edge_weight_by_id(&self, src: VertexId, dst: VertexId) -> Option<W> {
+        self.adjacency_lists.get(src.index())?.get(&dst).map(|edge| edge.weight)
     }
 }
 
@@ -480,11 +596,17 @@ mod test {
         g.add_edge(&id1, &id3);
         g.add_edge(&id2, &id1);
 
-        let s = format!("{}", g);
-        // assert_eq!("a->b,a->c;b->a;", format!("{}", g)) // ; this doesnt work because order isn't guaranteed iterating over hash map
-        assert_eq!(s.contains("a->b"), true);
-        assert_eq!(s.contains("a->c"), true);
-        assert_eq!(s.contains("b->a;"), true);
+        assert_eq!("a->b,a->c;b->a;", format!("{}", g));
+        assert_eq!("a -> b, c\nb -> a\nc", format!("{:#}", g));
+
+        // isolated vertices are shown, and a vertex with no edges left doesn't break the output
+        g.add_vertex(&String::from("d"), &Foo::new());
+        g.remove_edge(&id2, &id1);
+        assert_eq!("a->b,a->c;d;", format!("{}", g));
+        assert_eq!(
+            "a[Foo { property: 0 }]->b:1,a->c:1;b[Foo { property: 0 }];c[Foo { property: 0 }];d[Foo { property: 0 }];",
+            g.display().with_weights().with_values().to_string()
+        );
     }
 
     #[test]
@@ -559,4 +681,302 @@ mod test {
         // path to nonexistant vertex
         assert_eq!(g.shortest_path(&id1, &"foo".to_string()).len(), 0);
     }
+
+    #[test]
+    fn undirected_graph() {
+        let mut g: Graph<Foo> = Graph::new_undirected();
+        let id1 = String::from("a");
+        let id2 = String::from("b");
+        let id3 = String::from("c");
+
+        g.add_vertex(&id1, &Foo::new());
+        g.add_vertex(&id2, &Foo::new());
+        g.add_vertex(&id3, &Foo::new());
+        assert!(!g.is_directed());
+
+        // an edge goes both ways and can only be added once
+        assert!(g.add_weighted_edge(&id2, &id1, 3));
+        assert!(!g.add_edge(&id1, &id2));
+        assert!(g.add_edge(&id2, &id3));
+        assert!(g.has_edge(&id1, &id2));
+        assert!(g.has_edge(&id2, &id1));
+        assert!(g.get_adjacent_verts(&id1).unwrap().contains(&id2));
+        assert_eq!(g.get_adjacent_verts(&id2).unwrap().len(), 2);
+        assert_eq!(g.edge_count(), 2);
+        assert_eq!(g.edge_weight(&id1, &id2), Some(3));
+        assert_eq!(g.edge_weight(&id2, &id1), Some(3));
+
+        assert_eq!(g.shortest_path(&id1, &id3), ["a", "b", "c"]);
+        assert_eq!(g.shortest_path(&id3, &id1), ["c", "b", "a"]);
+
+        // each edge is printed once
+        let s = format!("{}", g);
+        assert!(s.contains("a-b"));
+        assert!(s.contains("b-c"));
+        assert!(!s.contains("b-a"));
+        assert!(!s.contains("c-b"));
+
+        // removing either direction removes the edge
+        assert!(g.remove_edge(&id2, &id1));
+        assert!(!g.has_edge(&id1, &id2));
+        assert!(!g.get_adjacent_verts(&id1).unwrap().contains(&id2));
+        assert!(!g.get_adjacent_verts(&id2).unwrap().contains(&id1));
+        assert_eq!(g.edge_count(), 1);
+
+        g.remove_vertex(&id3);
+        assert_eq!(g.edge_count(), 0);
+        assert_eq!(g.get_adjacent_verts(&id2).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn undirected_negative_edge() {
+        let mut g: Graph<Foo> = Graph::new_undirected();
+        let id1 = String::from("a");
+        let id2 = String::from("b");
+        g.add_vertex(&id1, &Foo::new());
+        g.add_vertex(&id2, &Foo::new());
+        g.add_weighted_edge(&id1, &id2, -1);
+
+        // a negative undirected edge can be walked back and forth forever
+        let err = g.bellman_ford(&id1).unwrap().unwrap_err();
+        assert_eq!(err.cycle().len(), 2);
+    }
+
+    // pairs of edges whose ids concatenate to the same string
+    const ADVERSARIAL_EDGES: [(&str, &str, &str, &str); 5] = [
+        ("ab", "c", "a", "bc"),
+        ("", "abc", "abc", ""),
+        ("a", "", "", "a"),
+        ("a->b", "c", "a", "b->c"),
+        ("\u{e9}", "e", "\u{e9}e", ""),
+    ];
+
+    #[test]
+    fn edge_identity_adversarial_ids() {
+        let mut g: Graph<Foo> = Graph::new();
+        for (a, b, c, d) in ADVERSARIAL_EDGES {
+            for id in [a, b, c, d] {
+                g.add_vertex(&id.to_string(), &Foo::new());
+            }
+        }
+
+        for (i, (a, b, c, d)) in ADVERSARIAL_EDGES.iter().enumerate() {
+            let (a, b, c, d) = (a.to_string(), b.to_string(), c.to_string(), d.to_string());
+            assert!(g.add_weighted_edge(&a, &b, i as i32), "{:?}->{:?}", a, b);
+            assert!(g.add_weighted_edge(&c, &d, 100 + i as i32), "{:?}->{:?}", c, d);
+            assert!(!g.add_edge(&c, &d));
+        }
+        assert_eq!(g.edge_count(), 2 * ADVERSARIAL_EDGES.len());
+
+        for (i, (a, b, c, d)) in ADVERSARIAL_EDGES.iter().enumerate() {
+            let (a, b, c, d) = (a.to_string(), b.to_string(), c.to_string(), d.to_string());
+            assert_eq!(g.edge_weight(&a, &b), Some(i as i32));
+            assert_eq!(g.edge_weight(&c, &d), Some(100 + i as i32));
+
+            // removing one edge of the pair leaves the other
+            assert!(g.remove_edge(&a, &b));
+            assert!(!g.has_edge(&a, &b));
+            assert!(g.has_edge(&c, &d));
+            assert_eq!(g.shortest_path(&c, &d), [c.clone(), d.clone()]);
+        }
+        assert_eq!(g.edge_count(), ADVERSARIAL_EDGES.len());
+    }
+
+    #[test]
+    fn edge_identity_adversarial_ids_undirected() {
+        let mut g: Graph<Foo> = Graph::new_undirected();
+        for id in ["a", "ab", "bc", "c"] {
+            g.add_vertex(&id.to_string(), &Foo::new());
+        }
+        let (a, ab, bc, c) = ("a".to_string(), "ab".to_string(), "bc".to_string(), "c".to_string());
+
+        assert!(g.add_weighted_edge(&ab, &c, 1));
+        assert!(g.add_weighted_edge(&a, &bc, 2));
+        // the same edges from the other side
+        assert!(!g.add_edge(&c, &ab));
+        assert!(!g.add_edge(&bc, &a));
+        assert_eq!(g.edge_count(), 2);
+        assert_eq!(g.edge_weight(&c, &ab), Some(1));
+        assert_eq!(g.edge_weight(&bc, &a), Some(2));
+
+        assert!(g.remove_edge(&c, &ab));
+        assert!(g.has_edge(&a, &bc));
+        assert_eq!(g.edge_count(), 1);
+    }
+
+    #[test]
+    fn vertex_handles() {
+        let mut g: Graph<Foo> = Graph::new();
+        let id1 = String::from("a");
+        let id2 = String::from("b");
+
+        let a = g.intern_vertex(&id1, &Foo { property: 1 });
+        let b = g.intern_vertex(&id2, &Foo::new());
+        // interning an existing key gives back the same handle and keeps the value
+        assert_eq!(g.intern_vertex(&id1, &Foo::new()), a);
+        assert_eq!(g.vertex_id(&id1), Some(a));
+        assert_eq!(g.vertex_key(b), Some(&id2));
+        assert_eq!(g.value(a), Some(&Foo { property: 1 }));
+
+        g.value_mut(b).unwrap().property = 5;
+        assert_eq!(g.get_value(&id2), Some(Foo { property: 5 }));
+
+        assert!(g.add_edge_by_id(a, b));
+        assert!(!g.add_edge_by_id(a, b));
+        assert!(g.has_edge(&id1, &id2));
+        assert!(g.has_edge_by_id(a, b));
+        assert!(!g.has_edge_by_id(b, a));
+        assert_eq!(g.adjacent_ids(a).collect::<Vec<_>>(), [b]);
+        assert_eq!(g.shortest_path_by_id(a, b), [a, b]);
+
+        assert!(g.remove_edge_by_id(a, b));
+        assert!(!g.has_edge(&id1, &id2));
+
+        // a removed vertex's handle stays invalid, even if the key is added again
+        g.add_edge_by_id(a, b);
+        assert_eq!(g.remove_vertex_by_id(a), Some(Foo { property: 1 }));
+        assert_eq!(g.edge_count(), 0);
+        assert_eq!(g.value(a), None);
+        assert_eq!(g.vertex_key(a), None);
+        assert!(!g.add_edge_by_id(a, b));
+        let c = g.intern_vertex(&id1, &Foo::new());
+        assert_ne!(a, c);
+        assert_eq!(g.remove_vertex_by_id(a), None);
+        assert_eq!(g.vertex_count(), 2);
+    }
+
+    #[test]
+    fn generic_keys() {
+        let mut g: Graph<&str, i32, (i32, i32)> = Graph::new_undirected();
+        for x in 0..3 {
+            for y in 0..3 {
+                g.add_vertex(&(x, y), &"cell");
+            }
+        }
+        for x in 0..3 {
+            for y in 0..3 {
+                g.add_edge(&(x, y), &(x + 1, y));
+                g.add_edge(&(x, y), &(x, y + 1));
+            }
+        }
+        assert_eq!(g.edge_count(), 12);
+        assert_eq!(g.get_adjacent_verts(&(1, 1)).unwrap().len(), 4);
+        assert_eq!(g.shortest_path(&(0, 0), &(0, 2)), [(0, 0), (0, 1), (0, 2)]);
+        assert_eq!(g.search(&(0, 0), &(2, 2)), Some((2, 2)));
+
+        g.remove_vertex(&(0, 1));
+        assert_eq!(g.shortest_path(&(0, 0), &(0, 2)).len(), 5);
+        assert_eq!(g.get_value(&(2, 2)), Some("cell"));
+    }
+
+    #[test]
+    fn borrowing_iterators() {
+        let mut g: Graph<Foo> = Graph::new();
+        let (a, b, c, d) = ("a".to_string(), "b".to_string(), "c".to_string(), "d".to_string());
+        for (i, id) in [&a, &b, &c, &d].into_iter().enumerate() {
+            g.add_vertex(id, &Foo { property: i as i32 });
+        }
+        g.add_weighted_edge(&a, &b, 1);
+        g.add_weighted_edge(&a, &c, 2);
+        g.add_weighted_edge(&c, &a, 3);
+        g.add_weighted_edge(&b, &b, 4);
+
+        let vertices: Vec<_> = g.vertices().map(|(id, value)| (id.as_str(), value.property)).collect();
+        assert_eq!(vertices, [("a", 0), ("b", 1), ("c", 2), ("d", 3)]);
+
+        let mut edges: Vec<_> = g.edges().map(|(src, dst, w)| (src.as_str(), dst.as_str(), w)).collect();
+        edges.sort();
+        assert_eq!(edges, [("a", "b", 1), ("a", "c", 2), ("b", "b", 4), ("c", "a", 3)]);
+
+        let mut neighbors: Vec<_> = g.neighbors(&a).collect();
+        neighbors.sort();
+        assert_eq!(neighbors, [&b, &c]);
+        let mut in_neighbors: Vec<_> = g.in_neighbors(&b).collect();
+        in_neighbors.sort();
+        assert_eq!(in_neighbors, [&a, &b]);
+        assert_eq!(g.in_neighbors(&d).count(), 0);
+        assert_eq!(g.neighbors(&"foo".to_string()).count(), 0);
+        assert_eq!(g.in_neighbors(&"foo".to_string()).count(), 0);
+
+        assert_eq!(g.out_degree(&a), Some(2));
+        assert_eq!(g.in_degree(&a), Some(1));
+        assert_eq!(g.out_degree(&d), Some(0));
+        assert_eq!(g.in_degree(&b), Some(2));
+        assert_eq!(g.out_degree(&"foo".to_string()), None);
+        assert_eq!(g.in_degree(&"foo".to_string()), None);
+    }
+
+    #[test]
+    fn borrowing_iterators_undirected() {
+        let mut g: Graph<Foo> = Graph::new_undirected();
+        let (a, b, c) = ("a".to_string(), "b".to_string(), "c".to_string());
+        for id in [&a, &b, &c] {
+            g.add_vertex(id, &Foo::new());
+        }
+        g.add_edge(&b, &a);
+        g.add_edge(&c, &b);
+
+        // each edge once, from the vertex added first
+        let edges: Vec<_> = g.edges().map(|(src, dst, _)| (src.as_str(), dst.as_str())).collect();
+        assert_eq!(edges, [("a", "b"), ("b", "c")]);
+        assert_eq!(g.neighbors(&b).count(), 2);
+        assert_eq!(g.in_degree(&b), Some(2));
+        assert_eq!(g.out_degree(&a), Some(1));
+        assert_eq!(g.in_neighbors(&a).collect::<Vec<_>>(), [&b]);
+    }
+
+    #[test]
+    fn in_adjacency_index() {
+        let mut g: Graph<Foo> = Graph::new();
+        let (a, b, c) = ("a".to_string(), "b".to_string(), "c".to_string());
+        for id in [&a, &b, &c] {
+            g.add_vertex(id, &Foo::new());
+        }
+        g.add_weighted_edge(&a, &b, 1);
+        g.add_weighted_edge(&c, &b, 2);
+        g.add_weighted_edge(&b, &b, 3);
+        g.add_weighted_edge(&b, &c, 4);
+        assert_eq!(g.in_degree(&b), Some(3));
+        assert_eq!(g.in_degree(&c), Some(1));
+
+        g.remove_edge(&c, &b);
+        assert_eq!(g.in_degree(&b), Some(2));
+        assert!(!g.in_neighbors(&b).any(|x| *x == c));
+
+        // removing b takes its in edges, out edges and self loop with it
+        g.remove_vertex(&b);
+        assert_eq!(g.edge_count(), 0);
+        assert_eq!(g.out_degree(&a), Some(0));
+        assert_eq!(g.in_degree(&c), Some(0));
+
+        // b comes back without any of its old edges
+        g.add_vertex(&b, &Foo::new());
+        assert_eq!(g.in_degree(&b), Some(0));
+        g.add_edge(&a, &b);
+        assert_eq!(g.in_neighbors(&b).collect::<Vec<_>>(), [&a]);
+    }
+
+    #[test]
+    fn transpose() {
+        let mut g: Graph<Foo> = Graph::new();
+        let (a, b, c) = ("a".to_string(), "b".to_string(), "c".to_string());
+        for id in [&a, &b, &c] {
+            g.add_vertex(id, &Foo::new());
+        }
+        g.add_weighted_edge(&a, &b, 1);
+        g.add_weighted_edge(&b, &c, 2);
+        g.add_weighted_edge(&c, &c, 3);
+
+        let t = g.transpose();
+        assert_eq!(t.edge_count(), 3);
+        assert_eq!(t.edge_weight(&b, &a), Some(1));
+        assert_eq!(t.edge_weight(&c, &b), Some(2));
+        assert_eq!(t.edge_weight(&c, &c), Some(3));
+        assert!(!t.has_edge(&a, &b));
+        assert_eq!(t.shortest_path(&c, &a), ["c", "b", "a"]);
+        assert_eq!(t.out_degree(&a), Some(0));
+        assert_eq!(t.in_neighbors(&a).collect::<Vec<_>>(), [&b]);
+        assert_eq!(t.in_degree(&b), Some(1));
+        assert_eq!(t.vertex_id(&b), g.vertex_id(&b));
+    }
 }
@@ -0,0 +1,377 @@
+use super::shortest_path::{bellman_ford, dijkstra_by, negative_cycle};
+use super::{Graph, NegativeCycle, VertexId, Weight};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// shortest distances and next hops between every pair of vertices, see Graph::all_pairs_shortest_paths.
+// vertices are indexed in the order they were added to the graph, and the matrices are stored row major
+#[derive(Debug, Clone)]
+pub struct DistanceMatrix<W, K = String> {
+    ids: Vec<K>,              // index to vertex key
+    index: HashMap<K, usize>, // vertex key to index
+    dist: Vec<W>,             // dist[i * V + j] is the distance from i to j, infinity if there is no path
+    next: Vec<Option<usize>>, // next[i * V + j] is the vertex after i on the shortest path from i to j
+}
+
+impl<W, K> DistanceMatrix<W, K>
+where
+    W: Weight,
+    K: Hash + Eq + Clone,
+{
+    // a matrix where every vertex only reaches itself
+    fn new(ids: Vec<K>) -> Self {
+        let n = ids.len();
+        let index = ids.iter().enumerate().map(|(i, id)| (id.clone(), i)).collect();
+
+        let mut dist = vec![W::infinity(); n * n];
+        let mut next = vec![None; n * n];
+        for i in 0..n {
+            dist[i * n + i] = W::zero();
+            next[i * n + i] = Some(i);
+        }
+        DistanceMatrix { ids, index, dist, next }
+    }
+
+    // keys of the vertices in the matrix, in index order
+    pub fn vertices(&self) -> &[K] {
+        &self.ids
+    }
+
+    // distance of the shortest path from src to dst, None if either is missing or there is no path
+    pub fn distance(&self, src: &K, dst: &K) -> Option<W> {
+        let (i, j) = (*self.index.get(src)?, *self.index.get(dst)?);
+        self.next[self.cell(i, j)]?;
+        Some(self.dist[self.cell(i, j)])
+    }
+
+    // the vertex after src on the shortest path from src to dst. for src == dst this is src itself
+    pub fn next_hop(&self, src: &K, dst: &K) -> Option<&K> {
+        let (i, j) = (*self.index.get(src)?, *self.index.get(dst)?);
+        self.next[self.cell(i, j)].map(|k| &self.ids[k])
+    }
+
+    // rebuilds the shortest path from src to dst by following next hops
+    pub fn path(&self, src: &K, dst: &K) -> Option<Vec<K>> {
+        let (mut i, j) = (*self.index.get(src)?, *self.index.get(dst)?);
+        self.next[self.cell(i, j)]?;
+
+        let mut path = vec![src.clone()];
+        while i != j {
+            i = self.next[self.cell(i, j)].unwrap();
+            path.push(self.ids[i].clone());
+        }
+        Some(path)
+    }
+
+    fn cell(&self, i: usize, j: usize) -> usize {
+        i * self.ids.len() + j
+    }
+}
+
+impl<W, K> PartialEq for DistanceMatrix<W, K>
+where
+    W: PartialEq,
+    K: Hash + Eq,
+{
+    fn eq(&self, rhs: &Self) -> bool {
+        self.ids == rhs.ids && self.dist == rhs.dist && self.next == rhs.next
+    }
+}
+
+impl<T, W, K, E> Graph<T, W, K, E>
+where
+    T: Default + Clone,
+    W: Weight,
+    K: Hash + Eq + Clone,
+    E: Default + Clone,
+{
+    // shortest paths between every pair of vertices. uses floyd_warshall for dense graphs and johnson for sparse ones.
+    // returns a NegativeCycle error if the graph has a negative cycle anywhere
+    pub fn all_pairs_shortest_paths(&self) -> Result<DistanceMatrix<W, K>, NegativeCycle<K>> {
+        // johnson is O(V*E*log(V)) and floyd-warshall is O(V^3)
+        let n = self.vertex_count() as f64;
+        if (self.edge_count() as f64) * n.log2().max(1.0) < n * n {
+            self.johnson()
+        } else {
+            self.floyd_warshall()
+        }
+    }
+
+    // johnson's algorithm. reweights the edges so none are negative, using bellman-ford potentials
+    // h(x) = the shortest distance to x from a virtual source with a zero weight edge to every vertex,
+    // then runs dijkstra from every vertex. O(V*E*log(V))
+    pub fn johnson(&self) -> Result<DistanceMatrix<W, K>, NegativeCycle<K>> {
+        let h = self.potentials().map_err(|cycle| cycle.map(|v| self.verts.key(v).clone()))?;
+        let (mut matrix, position) = self.empty_matrix();
+        let n = matrix.ids.len();
+
+        // w'(x, y) = w(x, y) + h(x) - h(y) is never negative and keeps the same shortest paths. potentials are never
+        // positive, so adding h(x) first can't overflow for w >= 0 and subtracting h(y) first can't for w < 0.
+        // an arc whose w' can't be represented is skipped
+        let reweighted = |x: VertexId, y: VertexId, w: W| {
+            let (hx, hy) = (h[x.index()], h[y.index()]);
+            if w < W::zero() {
+                w.checked_sub(hy)?.checked_add(hx)
+            } else {
+                w.checked_add(hx)?.checked_sub(hy)
+            }
+        };
+
+        for src in self.verts.ids() {
+            let i = position[src.index()];
+            let search = dijkstra_by(self, src, reweighted);
+
+            // first_hop[x] is the index of the vertex after src on the path to x
+            let mut first_hop: Vec<Option<usize>> = vec![None; self.verts.slots()];
+            for dst in self.verts.ids() {
+                // d = w'(path) = w(path) + h(src) - h(dst). d >= 0 and h(dst) <= 0 so adding h(dst) first can't overflow,
+                // and only a distance that can't be represented is left at infinity
+                let d = search.dist[dst.index()].and_then(|d| d.checked_add(h[dst.index()])?.checked_sub(h[src.index()]));
+                let Some(d) = d else {
+                    continue;
+                };
+                let j = position[dst.index()];
+                matrix.dist[i * n + j] = d;
+                if i != j {
+                    matrix.next[i * n + j] = Some(first_hop_of(&search.prev, src, dst, &position, &mut first_hop));
+                }
+            }
+        }
+        Ok(matrix)
+    }
+
+    // bellman-ford from a virtual source with a zero weight edge to every vertex.
+    // starting every distance at zero stands in for the first pass over the virtual edges
+    fn potentials(&self) -> Result<Vec<W>, NegativeCycle<VertexId>> {
+        let mut h: Vec<W> = vec![W::zero(); self.verts.slots()];
+        let mut prev: Vec<Option<VertexId>> = vec![None; self.verts.slots()];
+
+        let mut relaxed: Option<VertexId> = None;
+        for _ in 0..self.verts.len() {
+            relaxed = None;
+            for (src, dst, w) in self.arcs() {
+                let Some(alt) = h[src.index()].checked_add(w) else {
+                    continue;
+                };
+                if alt < h[dst.index()] {
+                    h[dst.index()] = alt;
+                    prev[dst.index()] = Some(src);
+                    relaxed = Some(dst);
+                }
+            }
+            if relaxed.is_none() {
+                break;
+            }
+        }
+
+        match relaxed {
+            None => Ok(h),
+            Some(v) => Err(negative_cycle(&prev, v, self.verts.len())),
+        }
+    }
+}
+
+impl<T, W, K, E> Graph<T, W, K, E>
+where
+    T: Default + Clone,
+    W: Weight,
+    K: Hash + Eq + Clone,
+    E: Default + Clone,
+{
+    // floyd-warshall algorithm. O(V^3) time and O(V^2) memory regardless of the number of edges.
+    // returns a NegativeCycle error if the graph has a negative cycle anywhere
+    pub fn floyd_warshall(&self) -> Result<DistanceMatrix<W, K>, NegativeCycle<K>> {
+        let (mut matrix, position) = self.empty_matrix();
+        let n = matrix.ids.len();
+
+        for (src, dst, w) in self.arcs() {
+            let (i, j) = (position[src.index()], position[dst.index()]);
+            if w < matrix.dist[i * n + j] {
+                matrix.dist[i * n + j] = w;
+                matrix.next[i * n + j] = Some(j);
+            }
+        }
+
+        // after round k, dist[i][j] is the shortest path from i to j that only passes through the first k vertices
+        for k in 0..n {
+            for i in 0..n {
+                if matrix.next[i * n + k].is_none() {
+                    continue;
+                }
+                let dist_ik = matrix.dist[i * n + k];
+                for j in 0..n {
+                    if matrix.next[k * n + j].is_none() {
+                        continue;
+                    }
+                    let Some(alt) = dist_ik.checked_add(matrix.dist[k * n + j]) else {
+                        continue;
+                    };
+                    if matrix.next[i * n + j].is_none() || alt < matrix.dist[i * n + j] {
+                        matrix.dist[i * n + j] = alt;
+                        matrix.next[i * n + j] = matrix.next[i * n + k];
+                    }
+                }
+            }
+        }
+
+        // a vertex that can reach itself with a negative distance is on a negative cycle,
+        // bellman-ford from that vertex finds the cycle
+        for v in self.verts.ids() {
+            let i = position[v.index()];
+            if matrix.dist[i * n + i] < W::zero() {
+                if let Err(cycle) = bellman_ford(self, v) {
+                    return Err(cycle.map(|v| self.verts.key(v).clone()));
+                }
+            }
+        }
+        Ok(matrix)
+    }
+
+    // a matrix over every vertex of the graph, and the matrix index of each vertex handle
+    fn empty_matrix(&self) -> (DistanceMatrix<W, K>, Vec<usize>) {
+        let mut position = vec![usize::MAX; self.verts.slots()];
+        let mut ids = Vec::with_capacity(self.verts.len());
+        for (i, (v, vertex)) in self.verts.iter().enumerate() {
+            position[v.index()] = i;
+            ids.push(vertex.id.clone());
+        }
+        (DistanceMatrix::new(ids), position)
+    }
+}
+
+// index of the vertex after src on the path from src to dst in a shortest path search, memoized in first_hop
+fn first_hop_of(prev: &[Option<VertexId>], src: VertexId, dst: VertexId, position: &[usize], first_hop: &mut [Option<usize>]) -> usize {
+    // walk back until a vertex whose first hop is known, or whose predecessor is src
+    let mut unresolved: Vec<VertexId> = Vec::new();
+    let mut u = dst;
+    let hop = loop {
+        if let Some(hop) = first_hop[u.index()] {
+            break hop;
+        }
+        unresolved.push(u);
+        let p = prev[u.index()].unwrap();
+        if p == src {
+            break position[u.index()];
+        }
+        u = p;
+    };
+
+    for u in unresolved {
+        first_hop[u.index()] = Some(hop);
+    }
+    hop
+}
+
+#[cfg(test)]
+mod test {
+    use super::Graph;
+    use crate::graph::test_support::graph_from;
+
+    // textbook example with negative edges but no negative cycle
+    fn clrs() -> Graph<i32> {
+        graph_from(
+            true,
+            &[],
+            &[
+                ("1", "2", 3),
+                ("1", "3", 8),
+                ("1", "5", -4),
+                ("2", "4", 1),
+                ("2", "5", 7),
+                ("3", "2", 4),
+                ("4", "1", 2),
+                ("4", "3", -5),
+                ("5", "4", 6),
+            ],
+        )
+    }
+
+    // expected distances for clrs(), row i column j is the distance from i+1 to j+1
+    const CLRS_DIST: [[i32; 5]; 5] = [[0, 1, -3, 2, -4], [3, 0, -4, 1, -1], [7, 4, 0, 5, 3], [2, -1, -5, 0, -2], [8, 5, 1, 6, 0]];
+
+    #[test]
+    fn floyd_warshall_distances() {
+        let g = clrs();
+        let m = g.floyd_warshall().unwrap();
+        // in the order graph_from added them
+        assert_eq!(m.vertices(), ["1", "2", "3", "5", "4"]);
+        for (i, row) in CLRS_DIST.iter().enumerate() {
+            for (j, d) in row.iter().enumerate() {
+                assert_eq!(m.distance(&(i + 1).to_string(), &(j + 1).to_string()), Some(*d));
+            }
+        }
+        assert_eq!(m.next_hop(&"1".to_string(), &"2".to_string()), Some(&"5".to_string()));
+        assert_eq!(m.path(&"1".to_string(), &"2".to_string()).unwrap(), ["1", "5", "4", "3", "2"]);
+        assert_eq!(m.path(&"3".to_string(), &"3".to_string()).unwrap(), ["3"]);
+    }
+
+    #[test]
+    fn johnson_matches_floyd_warshall() {
+        let g = clrs();
+        let fw = g.floyd_warshall().unwrap();
+        let johnson = g.johnson().unwrap();
+        assert_eq!(fw, johnson);
+    }
+
+    #[test]
+    fn johnson_large_weights() {
+        // potentials of -100 and -30 around an edge close to i32::MAX
+        let g = graph_from(true, &[], &[("s", "a", -100), ("a", "b", i32::MAX - 50), ("b", "c", -30), ("c", "d", 100)]);
+        let johnson = g.johnson().unwrap();
+        assert_eq!(johnson, g.floyd_warshall().unwrap());
+        assert_eq!(johnson.distance(&"s".to_string(), &"c".to_string()), Some(i32::MAX - 180));
+        assert_eq!(johnson.distance(&"a".to_string(), &"c".to_string()), Some(i32::MAX - 80));
+        // a->d is i32::MAX + 20, though its reweighted distance fits
+        assert_eq!(johnson.distance(&"a".to_string(), &"d".to_string()), None);
+    }
+
+    #[test]
+    fn johnson_reweighting_overflow() {
+        // h(y) = -10 so the reduced cost of x->y would be i32::MAX + 10
+        let g = graph_from(true, &[], &[("a", "y", -10), ("x", "y", i32::MAX)]);
+        for m in [g.johnson().unwrap(), g.all_pairs_shortest_paths().unwrap()] {
+            assert_eq!(m.distance(&"a".to_string(), &"y".to_string()), Some(-10));
+            assert_eq!(m.distance(&"x".to_string(), &"y".to_string()), None);
+        }
+    }
+
+    #[test]
+    fn all_pairs_unreachable() {
+        let g = graph_from(true, &[], &[("a", "b", 2), ("b", "c", 3), ("d", "c", 1)]);
+        for m in [g.floyd_warshall().unwrap(), g.johnson().unwrap(), g.all_pairs_shortest_paths().unwrap()] {
+            assert_eq!(m.distance(&"a".to_string(), &"c".to_string()), Some(5));
+            assert_eq!(m.distance(&"a".to_string(), &"d".to_string()), None);
+            assert_eq!(m.next_hop(&"c".to_string(), &"a".to_string()), None);
+            assert_eq!(m.path(&"d".to_string(), &"a".to_string()), None);
+            assert_eq!(m.distance(&"a".to_string(), &"foo".to_string()), None);
+        }
+    }
+
+    #[test]
+    fn all_pairs_negative_cycle() {
+        let g = graph_from(true, &[], &[("a", "b", 1), ("b", "c", -3), ("c", "a", 1), ("c", "d", 1)]);
+
+        let err = g.floyd_warshall().unwrap_err();
+        assert_eq!(err.cycle().len(), 3);
+        let err = g.johnson().unwrap_err();
+        assert_eq!(err.cycle().len(), 3);
+        for (i, id) in err.cycle().iter().enumerate() {
+            assert!(g.has_edge(id, &err.cycle()[(i + 1) % 3]));
+        }
+    }
+
+    #[test]
+    fn all_pairs_float_weights() {
+        let mut g: Graph<i32, f64> = Graph::new();
+        for id in ["a", "b", "c"] {
+            g.add_vertex(&id.to_string(), &0);
+        }
+        g.add_weighted_edge(&"a".to_string(), &"b".to_string(), 0.5);
+        g.add_weighted_edge(&"b".to_string(), &"c".to_string(), -0.25);
+        g.add_weighted_edge(&"a".to_string(), &"c".to_string(), 0.5);
+
+        let m = g.johnson().unwrap();
+        assert_eq!(m.distance(&"a".to_string(), &"c".to_string()), Some(0.25));
+        assert_eq!(m.path(&"a".to_string(), &"c".to_string()).unwrap(), ["a", "b", "c"]);
+    }
+}
@@ -0,0 +1,384 @@
+use super::{Graph, VertexId, Weight};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+// weakly connected component of every vertex, see Graph::weakly_connected_components.
+// component ids count up from 0 in the order of the first vertex of each component to be added to the graph
+#[derive(Debug, Clone)]
+pub struct Components<K = String> {
+    pub component: HashMap<K, usize>, // vertex key to component id
+    pub sizes: Vec<usize>,            // number of vertices in each component, indexed by component id
+}
+
+impl<K> Components<K>
+where
+    K: Hash + Eq,
+{
+    // number of components
+    pub fn count(&self) -> usize {
+        self.sizes.len()
+    }
+
+    // tests whether the vertices x and y are in the same component
+    pub fn connected(&self, x: &K, y: &K) -> bool {
+        matches!((self.component.get(x), self.component.get(y)), (Some(a), Some(b)) if a == b)
+    }
+}
+
+impl<T, W, K, E> Graph<T, W, K, E>
+where
+    T: Default + Clone,
+    W: Weight,
+    K: Hash + Eq + Clone,
+    E: Default + Clone,
+{
+    // tarjan's algorithm. splits the graph into strongly connected components, the maximal sets of vertices
+    // that can all reach each other. components are returned in topological order, so every edge between two
+    // components goes from an earlier component to a later one
+    pub fn strongly_connected_components(&self) -> Vec<HashSet<K>> {
+        self.scc_ids()
+            .into_iter()
+            .map(|component| component.into_iter().map(|v| self.verts.key(v).clone()).collect())
+            .collect()
+    }
+
+    // strongly connected components by handle, in topological order
+    fn scc_ids(&self) -> Vec<Vec<VertexId>> {
+        // index[x] is the order x was discovered in, low[x] the smallest index reachable from x's dfs subtree
+        // through vertices that have not been assigned a component yet
+        let mut index: Vec<Option<usize>> = vec![None; self.verts.slots()];
+        let mut low: Vec<usize> = vec![0; self.verts.slots()];
+        let mut discovered = 0;
+        // discovered vertices without a component, in discovery order
+        let mut unassigned: Vec<VertexId> = Vec::new();
+        let mut on_unassigned: Vec<bool> = vec![false; self.verts.slots()];
+        let mut components: Vec<Vec<VertexId>> = Vec::new();
+        // the current dfs path with the unvisited neighbors of each vertex on it
+        let mut stack = Vec::new();
+
+        for root in self.verts.ids() {
+            if index[root.index()].is_some() {
+                continue;
+            }
+
+            index[root.index()] = Some(discovered);
+            low[root.index()] = discovered;
+            discovered += 1;
+            unassigned.push(root);
+            on_unassigned[root.index()] = true;
+            stack.push((root, self.adjacent_ids(root)));
+
+            while let Some((u, neighbors)) = stack.last_mut() {
+                let u = *u;
+                match neighbors.next() {
+                    Some(v) => match index[v.index()] {
+                        None => {
+                            index[v.index()] = Some(discovered);
+                            low[v.index()] = discovered;
+                            discovered += 1;
+                            unassigned.push(v);
+                            on_unassigned[v.index()] = true;
+                            stack.push((v, self.adjacent_ids(v)));
+                        }
+                        Some(i) => {
+                            if on_unassigned[v.index()] && i < low[u.index()] {
+                                low[u.index()] = i;
+                            }
+                        }
+                    },
+                    None => {
+                        stack.pop();
+                        if let Some((parent, _)) = stack.last() {
+                            if low[u.index()] < low[parent.index()] {
+                                low[parent.index()] = low[u.index()];
+                            }
+                        }
+
+                        // u is the first vertex discovered in its component, everything after it is in the same component
+                        if Some(low[u.index()]) == index[u.index()] {
+                            let start = unassigned.iter().rposition(|&x| x == u).unwrap();
+                            let component: Vec<VertexId> = unassigned.drain(start..).collect();
+                            for x in &component {
+                                on_unassigned[x.index()] = false;
+                            }
+                            components.push(component);
+                        }
+                    }
+                }
+            }
+        }
+
+        // tarjan finishes components in reverse topological order
+        components.reverse();
+        components
+    }
+
+    // connected components of the undirected view of the graph, the maximal sets of vertices joined by
+    // paths when the direction of edges is ignored. for an undirected graph these are its connected components.
+    // components are ordered by the first of their vertices to be added to the graph
+    pub fn connected_components(&self) -> Vec<HashSet<K>> {
+        let (labels, count) = self.component_ids();
+        let mut components: Vec<HashSet<K>> = vec![HashSet::new(); count];
+        for (v, vertex) in self.verts.iter() {
+            components[labels[v.index()]].insert(vertex.id.clone());
+        }
+        components
+    }
+
+    // labels every vertex with its weakly connected component, the connected components of the graph when
+    // the direction of edges is ignored, and counts the vertices in each. see connected_components
+    pub fn weakly_connected_components(&self) -> Components<K> {
+        let (labels, count) = self.component_ids();
+        let mut components = Components {
+            component: HashMap::with_capacity(self.verts.len()),
+            sizes: vec![0; count],
+        };
+        for (v, vertex) in self.verts.iter() {
+            components.component.insert(vertex.id.clone(), labels[v.index()]);
+            components.sizes[labels[v.index()]] += 1;
+        }
+        components
+    }
+
+    // every weakly connected component as a graph of its own, ordered by component id. vertices keep their values
+    // and the order they were added in, and edges keep their weights and payloads
+    pub fn component_subgraphs(&self) -> Vec<Graph<T, W, K, E>> {
+        let (labels, count) = self.component_ids();
+        let mut graphs: Vec<Graph<T, W, K, E>> = (0..count)
+            .map(|_| Graph {
+                directed: self.directed,
+                ..Graph::new()
+            })
+            .collect();
+
+        // handle of every vertex in the graph of its component
+        let mut ids: Vec<VertexId> = vec![VertexId::new(0); self.verts.slots()];
+        for (v, vertex) in self.verts.iter() {
+            ids[v.index()] = graphs[labels[v.index()]].intern_vertex(&vertex.id, &vertex.value);
+        }
+        for (src, adjacency_list) in self.adjacency_lists.iter().enumerate() {
+            for (&dst, adjacent) in adjacency_list.iter().filter(|(dst, _)| self.directed || src <= dst.index()) {
+                let edge = self.edge_view(VertexId::new(src), dst, adjacent);
+                let g = &mut graphs[labels[src]];
+                g.add_edge_with_by_id(ids[src], ids[dst.index()], edge.weight(), edge.payload().clone());
+            }
+        }
+        graphs
+    }
+
+    // weakly connected component id of every VertexId slot, and the number of components.
+    // slots of removed vertices are labelled 0
+    fn component_ids(&self) -> (Vec<usize>, usize) {
+        let mut labels: Vec<Option<usize>> = vec![None; self.verts.slots()];
+        let mut count = 0;
+        for root in self.verts.ids() {
+            if labels[root.index()].is_some() {
+                continue;
+            }
+            labels[root.index()] = Some(count);
+
+            let mut queue: VecDeque<VertexId> = VecDeque::from([root]);
+            while let Some(u) = queue.pop_front() {
+                // in_adjacent_ids repeats the adjacent_ids of an undirected graph, the labels skip them
+                for v in self.adjacent_ids(u).chain(self.in_adjacent_ids(u)) {
+                    if labels[v.index()].is_none() {
+                        labels[v.index()] = Some(count);
+                        queue.push_back(v);
+                    }
+                }
+            }
+            count += 1;
+        }
+        (labels.into_iter().map(|label| label.unwrap_or(0)).collect(), count)
+    }
+
+    // the condensation of the graph, a directed acyclic graph with one vertex per strongly connected component.
+    // vertex i has the set of vertex keys in the i'th component of strongly_connected_components as its value.
+    // there is an edge between two components if there is any edge between their vertices,
+    // weighted by the lightest of those edges
+    pub fn condensation(&self) -> Graph<HashSet<K>, W, usize> {
+        let components = self.scc_ids();
+        let mut component_of: Vec<usize> = vec![0; self.verts.slots()];
+        let mut g: Graph<HashSet<K>, W, usize> = Graph::new();
+        for (i, component) in components.iter().enumerate() {
+            for v in component {
+                component_of[v.index()] = i;
+            }
+            g.add_vertex(&i, &component.iter().map(|&v| self.verts.key(v).clone()).collect());
+        }
+
+        for (src, dst, weight) in self.arcs() {
+            let (src, dst) = (&component_of[src.index()], &component_of[dst.index()]);
+            if src == dst {
+                continue;
+            }
+            match g.edge_weight(src, dst) {
+                Some(w) if w <= weight => {}
+                Some(_) => {
+                    g.set_edge_weight(src, dst, weight);
+                }
+                None => {
+                    g.add_weighted_edge(src, dst, weight);
+                }
+            }
+        }
+        g
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Graph;
+    use crate::graph::test_support::graph_from;
+    use std::collections::HashSet;
+
+    fn set(ids: &[&str]) -> HashSet<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    // textbook example with components {a, b, e}, {c, d}, {f, g}, {h}
+    fn clrs() -> Graph<i32> {
+        graph_from(
+            true,
+            &["a", "b", "c", "d", "e", "f", "g", "h"],
+            &[
+                ("a", "b", 1),
+                ("b", "c", 4),
+                ("b", "e", 1),
+                ("b", "f", 6),
+                ("c", "d", 1),
+                ("c", "g", 3),
+                ("d", "c", 1),
+                ("d", "h", 1),
+                ("e", "a", 1),
+                ("e", "f", 5),
+                ("f", "g", 1),
+                ("g", "f", 1),
+                ("g", "h", 2),
+                ("h", "h", 1),
+            ],
+        )
+    }
+
+    #[test]
+    fn strongly_connected_components() {
+        let g = clrs();
+        let components = g.strongly_connected_components();
+        assert_eq!(components, [set(&["a", "b", "e"]), set(&["c", "d"]), set(&["f", "g"]), set(&["h"])]);
+    }
+
+    #[test]
+    fn strongly_connected_components_dag() {
+        let edges = [("a", "b", 1), ("b", "c", 1), ("a", "c", 1), ("d", "b", 1)];
+        let g = graph_from(true, &["a", "b", "c", "d", "e"], &edges);
+        let components = g.strongly_connected_components();
+        assert_eq!(components.len(), 5);
+        assert!(components.iter().all(|c| c.len() == 1));
+
+        // every edge goes forwards
+        let position = |id: &str| components.iter().position(|c| c.contains(id)).unwrap();
+        for (src, dst, _) in edges {
+            assert!(position(src) < position(dst));
+        }
+    }
+
+    #[test]
+    fn condensation() {
+        let g = clrs();
+        let dag = g.condensation();
+
+        assert!(dag.is_acyclic());
+        assert_eq!(dag.get_value(&0), Some(set(&["a", "b", "e"])));
+        assert_eq!(dag.get_value(&3), Some(set(&["h"])));
+
+        // lightest of b->c (4)
+        assert_eq!(dag.edge_weight(&0, &1), Some(4));
+        // lightest of b->f (6) and e->f (5)
+        assert_eq!(dag.edge_weight(&0, &2), Some(5));
+        assert_eq!(dag.edge_weight(&1, &2), Some(3));
+        assert_eq!(dag.edge_weight(&1, &3), Some(1));
+        assert_eq!(dag.edge_weight(&2, &3), Some(2));
+        assert!(!dag.has_edge(&3, &3));
+        assert_eq!(dag.edge_count(), 5);
+    }
+
+    #[test]
+    fn large_cycle() {
+        // deep enough to overflow the stack if the search were recursive
+        let size = 100000;
+        let mut g: Graph<i32> = Graph::new();
+        for i in 0..size {
+            g.add_vertex(&i.to_string(), &0);
+        }
+        for i in 0..size {
+            g.add_edge(&i.to_string(), &((i + 1) % size).to_string());
+        }
+        let components = g.strongly_connected_components();
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0].len(), size);
+    }
+
+    #[test]
+    fn connected_components_undirected() {
+        let mut g: Graph<i32> = Graph::new_undirected();
+        for id in ["a", "b", "c", "d", "e", "f"] {
+            g.add_vertex(&id.to_string(), &0);
+        }
+        g.add_edge(&"a".to_string(), &"b".to_string());
+        g.add_edge(&"c".to_string(), &"b".to_string());
+        g.add_edge(&"e".to_string(), &"d".to_string());
+
+        let components = g.connected_components();
+        assert_eq!(components, [set(&["a", "b", "c"]), set(&["d", "e"]), set(&["f"])]);
+
+        // the strongly connected components of an undirected graph are its connected components
+        let mut strong = g.strongly_connected_components();
+        strong.sort_by_key(|c| c.iter().min().cloned());
+        assert_eq!(strong, components);
+    }
+
+    #[test]
+    fn connected_components_ignore_direction() {
+        let g = graph_from(true, &["a", "b", "c", "d", "e"], &[("b", "a", 1), ("b", "c", 1), ("e", "d", 1)]);
+        let components = g.connected_components();
+        assert_eq!(components, [set(&["a", "b", "c"]), set(&["d", "e"])]);
+    }
+
+    #[test]
+    fn weakly_connected_components() {
+        let mut g = graph_from(true, &["a", "b", "c", "d", "e", "f"], &[("b", "a", 2), ("c", "b", 3), ("e", "d", 1), ("d", "d", 4)]);
+        g.remove_vertex(&"c".to_string());
+        g.add_vertex(&"c".to_string(), &7);
+        g.add_edge(&"a".to_string(), &"c".to_string());
+
+        let components = g.weakly_connected_components();
+        assert_eq!(components.count(), 3);
+        assert_eq!(components.sizes, [3, 2, 1]);
+        assert_eq!(components.component[&"c".to_string()], 0);
+        assert_eq!(components.component[&"f".to_string()], 2);
+        assert!(components.connected(&"b".to_string(), &"c".to_string()));
+        assert!(!components.connected(&"a".to_string(), &"d".to_string()));
+        assert!(!components.connected(&"a".to_string(), &"x".to_string()));
+
+        let subgraphs = g.component_subgraphs();
+        assert_eq!(subgraphs.len(), 3);
+        assert_eq!(subgraphs[0].to_string(), "a->c;b->a;");
+        assert_eq!(subgraphs[0].get_value(&"c".to_string()), Some(7));
+        assert_eq!(subgraphs[0].edge_weight(&"b".to_string(), &"a".to_string()), Some(2));
+        assert_eq!(subgraphs[1].display().with_weights().to_string(), "d->d:4;e->d:1;");
+        assert_eq!(subgraphs[2].to_string(), "f;");
+        assert!(subgraphs.iter().all(|s| s.is_directed()));
+        let total: usize = subgraphs.iter().map(|s| s.edge_count()).sum();
+        assert_eq!(total, g.edge_count());
+
+        let mut g: Graph<i32, i32, String, &str> = Graph::new_undirected();
+        for id in ["a", "b", "c"] {
+            g.add_vertex(&id.to_string(), &0);
+        }
+        g.add_edge_with(&"c".to_string(), &"b".to_string(), 5, "bridge");
+        let subgraphs = g.component_subgraphs();
+        assert_eq!(g.weakly_connected_components().sizes, [1, 2]);
+        assert_eq!(subgraphs[1].edge_count(), 1);
+        assert_eq!(subgraphs[1].get_edge(&"b".to_string(), &"c".to_string()).unwrap().payload(), &"bridge");
+    }
+}
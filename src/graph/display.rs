@@ -0,0 +1,162 @@
+use super::{Graph, VertexId};
+use std::fmt;
+
+// formats a Graph, see Graph::display. vertices come in the order they were added and the edges of each vertex
+// in the order their destinations were added, so the output is the same every time.
+// {} gives the compact form a->b,a->c;b->a;d; with one group per vertex that has edges to print, and a bare
+// group for vertices with no edges at all. undirected edges use - and are printed once, from the vertex added first.
+// {:#} gives one line per vertex listing all of its neighbors, like a -> b, c
+pub struct GraphDisplay<'a, T, W, K, E = ()> {
+    graph: &'a Graph<T, W, K, E>,
+    weight: Option<fn(&W, &mut fmt::Formatter<'_>) -> fmt::Result>,
+    value: Option<fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result>,
+}
+
+impl<T, W, K, E> Graph<T, W, K, E> {
+    // formatter for the graph with options to show edge weights and vertex values. the graph's own Display
+    // is display() without options
+    pub fn display(&self) -> GraphDisplay<'_, T, W, K, E> {
+        GraphDisplay {
+            graph: self,
+            weight: None,
+            value: None,
+        }
+    }
+}
+
+impl<T, W, K, E> GraphDisplay<'_, T, W, K, E> {
+    // print the weight of each edge after its destination, as a->b:3 or a -> b (3)
+    pub fn with_weights(mut self) -> Self
+    where
+        W: fmt::Display,
+    {
+        self.weight = Some(|w, f| write!(f, "{}", w));
+        self
+    }
+
+    // print the value of every vertex in brackets after its key, using its Debug format. every vertex gets a group
+    // in the compact form so that its value is shown
+    pub fn with_values(mut self) -> Self
+    where
+        T: fmt::Debug,
+    {
+        self.value = Some(|value, f| write!(f, "{:?}", value));
+        self
+    }
+
+    // edges out of v ordered by destination. with once, undirected edges to vertices added earlier are left out
+    fn out_edges(&self, v: VertexId, once: bool) -> Vec<(VertexId, &W)> {
+        let g = self.graph;
+        let mut edges: Vec<(VertexId, &W)> = g.adjacency_lists[v.index()]
+            .iter()
+            .map(|(&u, edge)| (u, &edge.weight))
+            .filter(|&(u, _)| g.directed || !once || u >= v)
+            .collect();
+        edges.sort_by_key(|&(u, _)| u);
+        edges
+    }
+
+    fn write_vertex(&self, f: &mut fmt::Formatter<'_>, v: VertexId) -> fmt::Result
+    where
+        K: fmt::Display,
+    {
+        write!(f, "{}", self.graph.verts.key(v))?;
+        if let Some(write_value) = self.value {
+            write!(f, "[")?;
+            write_value(&self.graph.verts.get(v).unwrap().value, f)?;
+            write!(f, "]")?;
+        }
+        Ok(())
+    }
+}
+
+impl<T, W, K, E> fmt::Display for GraphDisplay<'_, T, W, K, E>
+where
+    K: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let g = self.graph;
+        let arrow = if g.directed { "->" } else { "-" };
+
+        if f.alternate() {
+            for (i, (v, _)) in g.verts.iter().enumerate() {
+                if i > 0 {
+                    writeln!(f)?;
+                }
+                self.write_vertex(f, v)?;
+                for (j, (u, w)) in self.out_edges(v, false).into_iter().enumerate() {
+                    if j == 0 {
+                        write!(f, " {} ", arrow)?;
+                    } else {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", g.verts.key(u))?;
+                    if let Some(write_weight) = self.weight {
+                        write!(f, " (")?;
+                        write_weight(w, f)?;
+                        write!(f, ")")?;
+                    }
+                }
+            }
+            return Ok(());
+        }
+
+        for (v, vertex) in g.verts.iter() {
+            let edges = self.out_edges(v, true);
+            let isolated = g.adjacency_lists[v.index()].is_empty() && (!g.directed || g.in_adjacency_lists[v.index()].is_empty());
+            if edges.is_empty() && !isolated && self.value.is_none() {
+                continue;
+            }
+
+            self.write_vertex(f, v)?;
+            for (i, (u, w)) in edges.into_iter().enumerate() {
+                if i > 0 {
+                    write!(f, ",{}", vertex.id)?;
+                }
+                write!(f, "{}{}", arrow, g.verts.key(u))?;
+                if let Some(write_weight) = self.weight {
+                    write!(f, ":")?;
+                    write_weight(w, f)?;
+                }
+            }
+            write!(f, ";")?;
+        }
+        Ok(())
+    }
+}
+
+impl<T, W, K, E> fmt::Display for Graph<T, W, K, E>
+where
+    K: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.display(), f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Graph;
+    use crate::graph::test_support::graph_from;
+
+    #[test]
+    fn display_options() {
+        let g = graph_from(true, &["a", "b", "c", "d"], &[("a", "c", 5), ("a", "b", 2), ("b", "a", 1)]);
+
+        assert_eq!(g.to_string(), "a->b,a->c;b->a;d;");
+        assert_eq!(g.display().with_weights().to_string(), "a->b:2,a->c:5;b->a:1;d;");
+        assert_eq!(g.display().with_values().to_string(), "a[0]->b,a->c;b[1]->a;c[2];d[3];");
+        assert_eq!(format!("{:#}", g), "a -> b, c\nb -> a\nc\nd");
+        assert_eq!(format!("{:#}", g.display().with_weights().with_values()), "a[0] -> b (2), c (5)\nb[1] -> a (1)\nc[2]\nd[3]");
+        assert_eq!(Graph::<i32>::new().to_string(), "");
+    }
+
+    #[test]
+    fn display_undirected() {
+        let g = graph_from(false, &["a", "b", "c", "d"], &[("c", "a", 3), ("b", "a", 1)]);
+
+        assert_eq!(g.to_string(), "a-b,a-c;d;");
+        assert_eq!(g.display().with_weights().to_string(), "a-b:1,a-c:3;d;");
+        assert_eq!(format!("{:#}", g.display().with_weights()), "a - b (1), c (3)\nb - a (1)\nc - a (3)\nd");
+    }
+}
@@ -0,0 +1,576 @@
+use super::{Graph, Weight};
+use std::fmt::{self, Display, Write};
+use std::hash::Hash;
+use std::str::FromStr;
+
+// converts vertex values to and from the attributes of a DOT node statement, see Graph::to_dot and Graph::from_dot.
+// implemented for the primitive types and String, which are kept in the label attribute
+pub trait DotValue: Sized {
+    // attributes written for a vertex holding self, as (name, value) pairs
+    fn to_attributes(&self) -> Vec<(String, String)>;
+
+    // value of a vertex read from the attributes of its node statement. the error message ends up in a DotError
+    fn from_attributes(attributes: &[(String, String)]) -> Result<Self, String>;
+}
+
+macro_rules! impl_dot_value {
+    ($($t:ty),*) => {
+        $(
+            impl DotValue for $t {
+                fn to_attributes(&self) -> Vec<(String, String)> {
+                    vec![("label".to_string(), self.to_string())]
+                }
+                fn from_attributes(attributes: &[(String, String)]) -> Result<Self, String> {
+                    match attributes.iter().rev().find(|(name, _)| name == "label") {
+                        Some((_, label)) => label.parse().map_err(|_| format!("invalid label {:?}", label)),
+                        None => Ok(Self::default()),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_dot_value!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, bool, char, String);
+
+impl DotValue for () {
+    fn to_attributes(&self) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    fn from_attributes(_attributes: &[(String, String)]) -> Result<Self, String> {
+        Ok(())
+    }
+}
+
+// error returned by Graph::from_dot
+#[derive(Debug, Clone, PartialEq)]
+pub struct DotError {
+    line: usize, // 1 based line of the input the error was found on
+    message: String,
+}
+
+impl DotError {
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for DotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for DotError {}
+
+impl<T, W, K, E> Graph<T, W, K, E>
+where
+    T: Default + Clone,
+    W: Weight,
+    K: Hash + Eq + Clone,
+    E: Default + Clone,
+{
+    // the graph in graphviz DOT format. vertices are written in the order they were added with the attributes of
+    // their values, then every edge ordered by source and destination with its weight in a weight attribute,
+    // so the same graph always gives the same text
+    pub fn to_dot(&self) -> String
+    where
+        T: DotValue,
+        W: Display,
+        K: Display,
+    {
+        let (keyword, edge_op) = if self.directed { ("digraph", "->") } else { ("graph", "--") };
+        let mut dot = format!("{} {{\n", keyword);
+        for (_, vertex) in self.verts.iter() {
+            let attributes = vertex.value.to_attributes();
+            write!(dot, "    {}", dot_id(&vertex.id.to_string())).unwrap();
+            if !attributes.is_empty() {
+                let attributes: Vec<String> = attributes.iter().map(|(name, value)| format!("{}={}", dot_id(name), dot_id(value))).collect();
+                write!(dot, " [{}]", attributes.join(", ")).unwrap();
+            }
+            dot.push_str(";\n");
+        }
+        for (src, dst, w) in self.sorted_edges() {
+            let (src, dst) = (self.verts.key(src).to_string(), self.verts.key(dst).to_string());
+            writeln!(dot, "    {} {} {} [weight={}];", dot_id(&src), edge_op, dot_id(&dst), dot_id(&w.to_string())).unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+impl<T, W, E> Graph<T, W, String, E>
+where
+    T: Default + Clone + DotValue,
+    W: Weight + FromStr,
+    E: Default + Clone,
+{
+    // builds a graph from the common subset of graphviz DOT: a graph or digraph with node statements, edge statements
+    // (chains like a -> b -> c included) and attribute lists. graph, node and edge attribute statements and graph
+    // attributes like rankdir=LR are accepted and ignored, subgraphs and ports are not supported.
+    // vertex values are made from the attributes of their node statement by DotValue, vertices that only appear in
+    // edges get T::default(). edge weights are read from the weight attribute, edges without one get W::one(),
+    // and every edge gets E::default()
+    pub fn from_dot(dot: &str) -> Result<Self, DotError> {
+        let tokens = tokenize(dot)?;
+        let last_line = dot.lines().count().max(1);
+        let mut parser = Parser { tokens, pos: 0, last_line };
+        parser.graph()
+    }
+}
+
+// id as written to DOT, quoted unless it is a plain identifier or a number
+fn dot_id(id: &str) -> String {
+    let identifier = id.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    let digits = id.strip_prefix('-').unwrap_or(id);
+    let number = !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit() || c == '.') && digits.matches('.').count() <= 1 && digits != ".";
+    if (identifier && keyword(id).is_none()) || number {
+        return id.to_string();
+    }
+    format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Keyword {
+    Strict,
+    Graph,
+    Digraph,
+    Node,
+    Edge,
+    Subgraph,
+}
+
+// keywords are case insensitive and only count when unquoted
+fn keyword(id: &str) -> Option<Keyword> {
+    match id.to_ascii_lowercase().as_str() {
+        "strict" => Some(Keyword::Strict),
+        "graph" => Some(Keyword::Graph),
+        "digraph" => Some(Keyword::Digraph),
+        "node" => Some(Keyword::Node),
+        "edge" => Some(Keyword::Edge),
+        "subgraph" => Some(Keyword::Subgraph),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Id(String),
+    Keyword(Keyword),
+    Punct(&'static str), // one of { } [ ] ; , = : -> --
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Id(id) => write!(f, "{:?}", id),
+            Token::Keyword(keyword) => write!(f, "{}", format!("{:?}", keyword).to_ascii_lowercase()),
+            Token::Punct(punct) => write!(f, "'{}'", punct),
+        }
+    }
+}
+
+fn error(line: usize, message: String) -> DotError {
+    DotError { line, message }
+}
+
+// splits DOT text into tokens with the line each one starts on, dropping comments
+fn tokenize(dot: &str) -> Result<Vec<(Token, usize)>, DotError> {
+    let chars: Vec<char> = dot.chars().collect();
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut i = 0;
+    let mut line_start = true; // only whitespace so far on this line, for # preprocessor lines
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            '\n' => {
+                line += 1;
+                line_start = true;
+                i += 1;
+                continue;
+            }
+            _ if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '#' if line_start => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            '/' if next == Some('/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            '/' if next == Some('*') => {
+                let start = line;
+                i += 2;
+                loop {
+                    match chars.get(i) {
+                        None => return Err(error(start, "unterminated comment".to_string())),
+                        Some('*') if chars.get(i + 1) == Some(&'/') => break,
+                        Some('\n') => line += 1,
+                        _ => {}
+                    }
+                    i += 1;
+                }
+                i += 2;
+                continue;
+            }
+            _ => line_start = false,
+        }
+
+        let start = line;
+        let token = match c {
+            '{' | '}' | '[' | ']' | ';' | ',' | '=' | ':' => {
+                i += 1;
+                Token::Punct(["{", "}", "[", "]", ";", ",", "=", ":"].into_iter().find(|p| p.starts_with(c)).unwrap())
+            }
+            '-' if next == Some('>') || next == Some('-') => {
+                i += 2;
+                Token::Punct(if next == Some('>') { "->" } else { "--" })
+            }
+            '"' => {
+                let mut id = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err(error(start, "unterminated string".to_string())),
+                        Some('"') => break,
+                        // \" and \\ are the escapes dot_id writes
+                        Some('\\') if matches!(chars.get(i + 1), Some('"' | '\\')) => {
+                            id.push(chars[i + 1]);
+                            i += 1;
+                        }
+                        // a backslash before a newline joins the lines
+                        Some('\\') if chars.get(i + 1) == Some(&'\n') => {
+                            line += 1;
+                            i += 1;
+                        }
+                        Some(&c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            id.push(c);
+                        }
+                    }
+                    i += 1;
+                }
+                i += 1;
+                Token::Id(id)
+            }
+            _ if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                let begin = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.') {
+                    i += 1;
+                }
+                let id: String = chars[begin..i].iter().collect();
+                match keyword(&id) {
+                    Some(keyword) => Token::Keyword(keyword),
+                    None => Token::Id(id),
+                }
+            }
+            _ => return Err(error(line, format!("unexpected character {:?}", c))),
+        };
+        tokens.push((token, start));
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    last_line: usize, // line reported for errors at the end of the input
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.last_line, |&(_, line)| line)
+    }
+
+    fn eat(&mut self, punct: &'static str) -> bool {
+        if self.peek() == Some(&Token::Punct(punct)) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn unexpected(&self, expected: &str) -> DotError {
+        match self.peek() {
+            Some(token) => error(self.line(), format!("expected {}, found {}", expected, token)),
+            None => error(self.line(), format!("expected {}, found end of input", expected)),
+        }
+    }
+
+    fn expect(&mut self, punct: &'static str) -> Result<(), DotError> {
+        if self.eat(punct) {
+            return Ok(());
+        }
+        Err(self.unexpected(&format!("'{}'", punct)))
+    }
+
+    fn id(&mut self) -> Result<String, DotError> {
+        match self.peek() {
+            Some(Token::Id(id)) => {
+                let id = id.clone();
+                self.pos += 1;
+                Ok(id)
+            }
+            _ => Err(self.unexpected("an id")),
+        }
+    }
+
+    // graph := [strict] (graph | digraph) [id] '{' stmt_list '}'
+    fn graph<T, W, E>(&mut self) -> Result<Graph<T, W, String, E>, DotError>
+    where
+        T: Default + Clone + DotValue,
+        W: Weight + FromStr,
+        E: Default + Clone,
+    {
+        if self.peek() == Some(&Token::Keyword(Keyword::Strict)) {
+            self.pos += 1;
+        }
+        let mut g = match self.peek() {
+            Some(Token::Keyword(Keyword::Digraph)) => Graph::new(),
+            Some(Token::Keyword(Keyword::Graph)) => Graph::new_undirected(),
+            _ => return Err(self.unexpected("graph or digraph")),
+        };
+        self.pos += 1;
+        if let Some(Token::Id(_)) = self.peek() {
+            self.pos += 1;
+        }
+        self.expect("{")?;
+
+        while !self.eat("}") {
+            self.statement(&mut g)?;
+            if !self.eat(";") {
+                self.eat(",");
+            }
+        }
+        if self.peek().is_some() {
+            return Err(self.unexpected("end of input"));
+        }
+        Ok(g)
+    }
+
+    fn statement<T, W, E>(&mut self, g: &mut Graph<T, W, String, E>) -> Result<(), DotError>
+    where
+        T: Default + Clone + DotValue,
+        W: Weight + FromStr,
+        E: Default + Clone,
+    {
+        let line = self.line();
+        match self.peek() {
+            Some(Token::Keyword(Keyword::Graph | Keyword::Node | Keyword::Edge)) => {
+                self.pos += 1;
+                self.attributes()?;
+                return Ok(());
+            }
+            Some(Token::Keyword(Keyword::Subgraph)) | Some(Token::Punct("{")) => return Err(error(line, "subgraphs are not supported".to_string())),
+            _ => {}
+        }
+
+        let first = self.id()?;
+        // graph attribute like rankdir=LR
+        if self.eat("=") {
+            self.id()?;
+            return Ok(());
+        }
+
+        let mut chain = vec![first];
+        while let Some(Token::Punct(op @ ("->" | "--"))) = self.peek() {
+            if (*op == "->") != g.is_directed() {
+                let message = format!("{} in a {}", op, if g.is_directed() { "digraph" } else { "graph" });
+                return Err(error(self.line(), message));
+            }
+            self.pos += 1;
+            chain.push(self.id()?);
+        }
+        if self.peek() == Some(&Token::Punct(":")) {
+            return Err(error(self.line(), "ports are not supported".to_string()));
+        }
+        let attributes = self.attributes()?;
+
+        for id in &chain {
+            g.add_vertex(id, &T::default());
+        }
+        if chain.len() == 1 {
+            if !attributes.is_empty() {
+                let value = T::from_attributes(&attributes).map_err(|message| error(line, message))?;
+                g.set_value(&chain[0], &value);
+            }
+            return Ok(());
+        }
+
+        let weight = match attributes.iter().rev().find(|(name, _)| name == "weight") {
+            Some((_, weight)) => weight.parse().map_err(|_| error(line, format!("invalid weight {:?}", weight)))?,
+            None => W::one(),
+        };
+        for pair in chain.windows(2) {
+            g.add_weighted_edge(&pair[0], &pair[1], weight);
+        }
+        Ok(())
+    }
+
+    // attr_list := ('[' (id '=' id [';' | ','])* ']')*
+    fn attributes(&mut self) -> Result<Vec<(String, String)>, DotError> {
+        let mut attributes = Vec::new();
+        while self.eat("[") {
+            while !self.eat("]") {
+                let name = self.id()?;
+                self.expect("=")?;
+                attributes.push((name, self.id()?));
+                if !self.eat(";") {
+                    self.eat(",");
+                }
+            }
+        }
+        Ok(attributes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DotValue, Graph};
+
+    #[derive(Debug, Clone, Default, PartialEq)]
+    struct Router {
+        name: String,
+        ports: u32,
+    }
+
+    impl DotValue for Router {
+        fn to_attributes(&self) -> Vec<(String, String)> {
+            vec![("label".to_string(), self.name.clone()), ("ports".to_string(), self.ports.to_string())]
+        }
+
+        fn from_attributes(attributes: &[(String, String)]) -> Result<Self, String> {
+            let mut router = Router::default();
+            for (name, value) in attributes {
+                match name.as_str() {
+                    "label" => router.name = value.clone(),
+                    "ports" => router.ports = value.parse().map_err(|_| format!("bad port count {}", value))?,
+                    _ => {}
+                }
+            }
+            Ok(router)
+        }
+    }
+
+    #[test]
+    fn write_dot() {
+        let mut g: Graph<i32> = Graph::new();
+        for (id, value) in [("b", 2), ("a", 1), ("my node", -3), ("node", 0)] {
+            g.add_vertex(&id.to_string(), &value);
+        }
+        g.add_weighted_edge(&"a".to_string(), &"b".to_string(), 5);
+        g.add_weighted_edge(&"b".to_string(), &"a".to_string(), 7);
+        g.add_weighted_edge(&"b".to_string(), &"my node".to_string(), -1);
+        g.add_edge(&"a".to_string(), &"node".to_string());
+
+        let expected = "digraph {
+    b [label=2];
+    a [label=1];
+    \"my node\" [label=-3];
+    \"node\" [label=0];
+    b -> a [weight=7];
+    b -> \"my node\" [weight=-1];
+    a -> b [weight=5];
+    a -> \"node\" [weight=1];
+}
+";
+        assert_eq!(g.to_dot(), expected);
+        assert_eq!(Graph::<i32>::from_dot(&g.to_dot()).unwrap().to_dot(), expected);
+
+        let mut g: Graph<()> = Graph::new_undirected();
+        g.add_vertex(&"x".to_string(), &());
+        g.add_vertex(&"y".to_string(), &());
+        g.add_edge(&"y".to_string(), &"x".to_string());
+        assert_eq!(g.to_dot(), "graph {\n    x;\n    y;\n    x -- y [weight=1];\n}\n");
+    }
+
+    #[test]
+    fn read_dot() {
+        let dot = r#"
+            /* backbone */
+            strict digraph core {
+                rankdir=LR; // ignored
+                node [shape=box]
+                edge [color="red"];
+                # a preprocessor line
+                r1 [label="Router \"one\"", ports=48];
+                r2 [label=r2 ports=24]
+                r1 -> r2 -> "r 3" [weight=10, color=blue];
+                r3 -> r1
+            }
+        "#;
+        let g: Graph<Router> = Graph::from_dot(dot).unwrap();
+        assert!(g.is_directed());
+        assert_eq!(g.vertex_count(), 4);
+        assert_eq!(g.edge_count(), 3);
+        assert_eq!(
+            g.get_value(&"r1".to_string()),
+            Some(Router {
+                name: "Router \"one\"".to_string(),
+                ports: 48
+            })
+        );
+        assert_eq!(g.get_value(&"r 3".to_string()), Some(Router::default()));
+        assert_eq!(g.edge_weight(&"r2".to_string(), &"r 3".to_string()), Some(10));
+        assert_eq!(g.edge_weight(&"r3".to_string(), &"r1".to_string()), Some(1));
+
+        // payloads and weights survive a round trip
+        let again: Graph<Router> = Graph::from_dot(&g.to_dot()).unwrap();
+        assert_eq!(again.to_dot(), g.to_dot());
+
+        // so do backslashes and quotes in keys and labels
+        let mut g: Graph<Router> = Graph::new();
+        let (key, name) = ("C:\\\"net\\".to_string(), "a\\b \"c\"\\".to_string());
+        g.add_vertex(&key, &Router { name: name.clone(), ports: 2 });
+        g.add_vertex(&"\\".to_string(), &Router::default());
+        g.add_edge(&key, &"\\".to_string());
+        let again: Graph<Router> = Graph::from_dot(&g.to_dot()).unwrap();
+        assert_eq!(again.get_value(&key), Some(Router { name, ports: 2 }));
+        assert!(again.has_edge(&key, &"\\".to_string()));
+        assert_eq!(again.to_dot(), g.to_dot());
+
+        let g: Graph<f64, f64> = Graph::from_dot("graph { a -- b [weight=2.5]; b [label=0.5] }").unwrap();
+        assert!(!g.is_directed());
+        assert_eq!(g.edge_weight(&"b".to_string(), &"a".to_string()), Some(2.5));
+        assert_eq!(g.get_value(&"b".to_string()), Some(0.5));
+    }
+
+    #[test]
+    fn dot_errors() {
+        let error = |dot: &str| Graph::<Router>::from_dot(dot).unwrap_err();
+
+        assert_eq!(error("digraph {\n a -> b\n c -- d\n}").line(), 3);
+        assert_eq!(error("digraph {\n a -> b\n c -- d\n}").message(), "-- in a digraph");
+        assert_eq!(error("digraph {\n  a [ports=many]\n}").to_string(), "line 2: bad port count many");
+        assert_eq!(error("digraph {\n a -> b [weight=x]\n}").to_string(), "line 2: invalid weight \"x\"");
+        assert_eq!(error("digraph {\n subgraph s { a }\n}").line(), 2);
+        assert_eq!(error("digraph {\n a:port -> b\n}").message(), "ports are not supported");
+        assert_eq!(error("digraph {\n a -> \n}").to_string(), "line 3: expected an id, found '}'");
+        assert_eq!(error("digraph {\n a -> b\n").to_string(), "line 2: expected an id, found end of input");
+        assert_eq!(error("\n\"abc").to_string(), "line 2: unterminated string");
+        assert_eq!(error("foo { }").to_string(), "line 1: expected graph or digraph, found \"foo\"");
+        assert_eq!(error("graph { } graph").message(), "expected end of input, found graph");
+    }
+}
@@ -0,0 +1,254 @@
+use super::{Graph, GraphView, VertexId, Weight};
+use std::hash::Hash;
+
+// an edge of a Graph, with its weight and a borrow of its payload. see Graph::get_edge
+#[derive(Debug, PartialEq)]
+pub struct Edge<'a, W, E> {
+    weight: W,
+    payload: &'a E,
+}
+
+impl<'a, W, E> Edge<'a, W, E> {
+    pub fn payload(&self) -> &'a E {
+        self.payload
+    }
+}
+
+impl<W, E> Edge<'_, W, E>
+where
+    W: Weight,
+{
+    pub fn weight(&self) -> W {
+        self.weight
+    }
+}
+
+impl<W: Copy, E> Clone for Edge<'_, W, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<W: Copy, E> Copy for Edge<'_, W, E> {}
+
+// an entry of an adjacency list. an undirected edge is in the adjacency lists of both of its ends, but only the
+// entry at the smaller VertexId holds the payload
+#[derive(Debug, Clone)]
+pub(super) struct Adjacent<W, E> {
+    pub(super) weight: W,
+    pub(super) payload: Option<E>,
+}
+
+// a Graph whose edge weights are computed from each edge by a cost function, see Graph::weighted_by
+pub struct WeightedBy<'a, T, W, K, E, F> {
+    graph: &'a Graph<T, W, K, E>,
+    cost: F,
+}
+
+impl<T, W, K, E> Graph<T, W, K, E>
+where
+    T: Default + Clone,
+    W: Weight,
+    K: Hash + Eq + Clone,
+    E: Default + Clone,
+{
+    // the edge src->dst with its weight and payload
+    pub fn get_edge(&self, src: &K, dst: &K) -> Option<Edge<'_, W, E>> {
+        self.get_edge_by_id(self.vertex_id(src)?, self.vertex_id(dst)?)
+    }
+
+    pub fn get_edge_by_id(&self, src: VertexId, dst: VertexId) -> Option<Edge<'_, W, E>> {
+        let adjacent = self.adjacency_lists.get(src.index())?.get(&dst)?;
+        Some(self.edge_view(src, dst, adjacent))
+    }
+
+    // changes the weight of the edge src->dst in place and returns the old weight. None if there is no such edge
+    pub fn set_edge_weight(&mut self, src: &K, dst: &K, weight: W) -> Option<W> {
+        self.set_edge_weight_by_id(self.vertex_id(src)?, self.vertex_id(dst)?, weight)
+    }
+
+    pub fn set_edge_weight_by_id(&mut self, src: VertexId, dst: VertexId, weight: W) -> Option<W> {
+        let edge = self.adjacency_lists.get_mut(src.index())?.get_mut(&dst)?;
+        let old = std::mem::replace(&mut edge.weight, weight);
+        if !self.directed {
+            self.adjacency_lists[dst.index()].get_mut(&src).unwrap().weight = weight;
+        }
+        Some(old)
+    }
+
+    // mutable access to the payload of the edge src->dst
+    pub fn edge_mut(&mut self, src: &K, dst: &K) -> Option<&mut E> {
+        self.edge_mut_by_id(self.vertex_id(src)?, self.vertex_id(dst)?)
+    }
+
+    pub fn edge_mut_by_id(&mut self, src: VertexId, dst: VertexId) -> Option<&mut E> {
+        let (src, dst) = if self.directed { (src, dst) } else { (src.min(dst), src.max(dst)) };
+        self.adjacency_lists.get_mut(src.index())?.get_mut(&dst)?.payload.as_mut()
+    }
+
+    // the edge src->dst given its adjacency list entry. the payload of an undirected edge is looked up at the
+    // smaller end if adjacent is the other one
+    pub(super) fn edge_view<'a>(&'a self, src: VertexId, dst: VertexId, adjacent: &'a Adjacent<W, E>) -> Edge<'a, W, E> {
+        let payload = match &adjacent.payload {
+            Some(payload) => payload,
+            None => self.adjacency_lists[dst.index()][&src].payload.as_ref().unwrap(),
+        };
+        Edge { weight: adjacent.weight, payload }
+    }
+
+    // a view of the graph where the weight of every edge is cost(edge), for running the GraphView queries such as
+    // shortest_path, find_path and astar on a cost derived from the payload. nothing is copied
+    pub fn weighted_by<C, F>(&self, cost: F) -> WeightedBy<'_, T, W, K, E, F>
+    where
+        C: Weight,
+        F: Fn(Edge<'_, W, E>) -> C,
+    {
+        WeightedBy { graph: self, cost }
+    }
+}
+
+impl<T, W, K, E, F, C> GraphView for WeightedBy<'_, T, W, K, E, F>
+where
+    T: Default + Clone,
+    W: Weight,
+    K: Hash + Eq + Clone,
+    E: Default + Clone,
+    C: Weight,
+    F: Fn(Edge<'_, W, E>) -> C,
+{
+    type Key = K;
+    type Value = T;
+    type Weight = C;
+
+    fn is_directed(&self) -> bool {
+        self.graph.directed
+    }
+
+    fn vertex_count(&self) -> usize {
+        self.graph.verts.len()
+    }
+
+    fn edge_count(&self) -> usize {
+        self.graph.edge_count
+    }
+
+    fn id_bound(&self) -> usize {
+        self.graph.verts.slots()
+    }
+
+    fn vertex_id(&self, id: &K) -> Option<VertexId> {
+        self.graph.verts.id(id)
+    }
+
+    fn vertex_key(&self, v: VertexId) -> Option<&K> {
+        self.graph.verts.get(v).map(|vertex| &vertex.id)
+    }
+
+    fn value(&self, v: VertexId) -> Option<&T> {
+        self.graph.verts.get(v).map(|vertex| &vertex.value)
+    }
+
+    fn vertex_ids(&self) -> impl Iterator<Item = VertexId> + '_ {
+        self.graph.verts.ids()
+    }
+
+    fn out_edges(&self, v: VertexId) -> impl Iterator<Item = (VertexId, C)> + '_ {
+        self.graph
+            .adjacency_lists
+            .get(v.index())
+            .into_iter()
+            .flatten()
+            .map(move |(&u, adjacent)| (u, (self.cost)(self.graph.edge_view(v, u, adjacent))))
+    }
+
+    fn edge_weight_by_id(&self, src: VertexId, dst: VertexId) -> Option<C> {
+        self.graph.get_edge_by_id(src, dst).map(&self.cost)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Graph, GraphView};
+
+    #[derive(Debug, Default, Clone, PartialEq)]
+    struct Link {
+        label: String,
+        km: u32,
+    }
+
+    fn link(label: &str, km: u32) -> Link {
+        Link { label: label.to_string(), km }
+    }
+
+    fn towns(directed: bool) -> Graph<(), i32, String, Link> {
+        let mut g = if directed { Graph::new() } else { Graph::new_undirected() };
+        for id in ["a", "b", "c"] {
+            g.add_vertex(&id.to_string(), &());
+        }
+        g.add_edge_with(&"a".to_string(), &"b".to_string(), 1, link("a1", 10));
+        g.add_edge_with(&"b".to_string(), &"c".to_string(), 1, link("b2", 10));
+        g.add_edge_with(&"a".to_string(), &"c".to_string(), 1, link("a3", 50));
+        g
+    }
+
+    #[test]
+    fn edge_payloads() {
+        let (a, b, c) = ("a".to_string(), "b".to_string(), "c".to_string());
+        let mut g = towns(true);
+        assert!(!g.add_edge_with(&a, &b, 2, link("dup", 0)));
+        assert!(g.add_edge(&c, &a));
+        assert_eq!(g.get_edge(&c, &a).unwrap().payload(), &Link::default());
+
+        let edge = g.get_edge(&a, &c).unwrap();
+        assert_eq!((edge.weight(), edge.payload().label.as_str()), (1, "a3"));
+        assert!(g.get_edge(&c, &b).is_none());
+
+        assert_eq!(g.set_edge_weight(&a, &c, 7), Some(1));
+        assert_eq!(g.edge_weight(&a, &c), Some(7));
+        assert_eq!(g.set_edge_weight(&c, &b, 7), None);
+
+        g.edge_mut(&a, &b).unwrap().km = 20;
+        assert_eq!(g.get_edge(&a, &b).unwrap().payload().km, 20);
+
+        assert_eq!(g.take_edge(&a, &c), Some((7, link("a3", 50))));
+        assert!(!g.has_edge(&a, &c));
+        assert_eq!(g.edge_count(), 3);
+    }
+
+    #[test]
+    fn undirected_payloads() {
+        let (a, b) = ("a".to_string(), "b".to_string());
+        let mut g = towns(false);
+        assert_eq!(g.get_edge(&b, &a).unwrap().payload().label, "a1");
+
+        g.edge_mut(&b, &a).unwrap().label.push('!');
+        assert_eq!(g.get_edge(&a, &b).unwrap().payload().label, "a1!");
+        assert_eq!(g.set_edge_weight(&b, &a, 4), Some(1));
+        assert_eq!(g.edge_weight(&a, &b), Some(4));
+        assert_eq!(g.transpose().get_edge(&a, &b), g.get_edge(&b, &a));
+        assert_eq!(g.display().with_weights().to_string(), "a-b:4,a-c:1;b-c:1;");
+
+        // the payload is kept once, whichever end the edge is reached from
+        assert_eq!(g.take_edge(&"c".to_string(), &a), Some((1, link("a3", 50))));
+        assert!(g.add_edge_with(&b, &b, 2, link("loop", 0)));
+        g.edge_mut(&b, &b).unwrap().km = 1;
+        assert_eq!(g.take_edge(&b, &b), Some((2, link("loop", 1))));
+    }
+
+    #[test]
+    fn weighted_by_payload() {
+        let (a, b, c) = ("a".to_string(), "b".to_string(), "c".to_string());
+        let mut g = towns(true);
+        // every edge has weight 1, so the direct edge is the shortest by weight but not by distance
+        assert_eq!(g.shortest_path(&a, &c), [a.clone(), c.clone()]);
+        let path = g.weighted_by(|edge| edge.payload().km).find_path(&a, &c).unwrap();
+        assert_eq!(path.vertices, [a.clone(), b.clone(), c.clone()]);
+        assert_eq!(path.cost, 20);
+
+        g.edge_mut(&b, &c).unwrap().km = 45;
+        let by_km = g.weighted_by(|edge| edge.payload().km);
+        assert_eq!(by_km.shortest_path(&a, &c), [a.clone(), c.clone()]);
+        assert_eq!(by_km.edge_weight_by_id(g.vertex_id(&b).unwrap(), g.vertex_id(&c).unwrap()), Some(45));
+        assert_eq!(by_km.search(&a, &c), Some(c));
+    }
+}
@@ -0,0 +1,148 @@
+use super::{Graph, GraphView, Path, ShortestPathTree, VertexId, Weight};
+use std::fmt;
+use std::hash::Hash;
+
+// reason a try_* method of Graph failed, naming the vertices involved
+#[derive(Debug, Clone, PartialEq)]
+pub enum GraphError<K = String> {
+    DuplicateVertex(K),    // a vertex with this key is already in the graph
+    MissingVertex(K),      // no vertex has this key
+    MissingSource(K),      // the source of an edge or path is not in the graph
+    MissingDestination(K), // the destination of an edge or path is not in the graph
+    DuplicateEdge(K, K),   // the edge src->dst is already in the graph
+    MissingEdge(K, K),     // there is no edge src->dst
+    Unreachable(K, K),     // there is no path from src to dst
+}
+
+impl<K> fmt::Display for GraphError<K>
+where
+    K: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphError::DuplicateVertex(id) => write!(f, "vertex {} already exists", id),
+            GraphError::MissingVertex(id) => write!(f, "vertex {} does not exist", id),
+            GraphError::MissingSource(id) => write!(f, "source vertex {} does not exist", id),
+            GraphError::MissingDestination(id) => write!(f, "destination vertex {} does not exist", id),
+            GraphError::DuplicateEdge(src, dst) => write!(f, "edge {}->{} already exists", src, dst),
+            GraphError::MissingEdge(src, dst) => write!(f, "edge {}->{} does not exist", src, dst),
+            GraphError::Unreachable(src, dst) => write!(f, "{} can not be reached from {}", dst, src),
+        }
+    }
+}
+
+impl<K> std::error::Error for GraphError<K> where K: fmt::Debug + fmt::Display {}
+
+// versions of the Graph methods that return a GraphError saying what went wrong, where the plain methods
+// return false, None or an empty Vec
+impl<T, W, K, E> Graph<T, W, K, E>
+where
+    T: Default + Clone,
+    W: Weight,
+    K: Hash + Eq + Clone,
+    E: Default + Clone,
+{
+    // see add_vertex. returns the handle of the new vertex
+    pub fn try_add_vertex(&mut self, id: &K, value: &T) -> Result<VertexId, GraphError<K>> {
+        match self.insert_vertex(id, value) {
+            (v, true) => Ok(v),
+            (_, false) => Err(GraphError::DuplicateVertex(id.clone())),
+        }
+    }
+
+    // see remove_vertex. returns the value of the removed vertex
+    pub fn try_remove_vertex(&mut self, id: &K) -> Result<T, GraphError<K>> {
+        self.remove_vertex(id).ok_or_else(|| GraphError::MissingVertex(id.clone()))
+    }
+
+    // see add_weighted_edge
+    pub fn try_add_weighted_edge(&mut self, src: &K, dst: &K, weight: W) -> Result<(), GraphError<K>> {
+        let (u, v) = self.edge_ends(src, dst)?;
+        if !self.add_weighted_edge_by_id(u, v, weight) {
+            return Err(GraphError::DuplicateEdge(src.clone(), dst.clone()));
+        }
+        Ok(())
+    }
+
+    // see add_edge
+    pub fn try_add_edge(&mut self, src: &K, dst: &K) -> Result<(), GraphError<K>> {
+        self.try_add_weighted_edge(src, dst, W::one())
+    }
+
+    // see remove_edge. returns the weight of the removed edge
+    pub fn try_remove_edge(&mut self, src: &K, dst: &K) -> Result<W, GraphError<K>> {
+        let (u, v) = self.edge_ends(src, dst)?;
+        let (weight, _) = self.take_edge_by_id(u, v).ok_or_else(|| GraphError::MissingEdge(src.clone(), dst.clone()))?;
+        Ok(weight)
+    }
+
+    // see get_value. borrows the value instead of cloning it
+    pub fn try_get_value(&self, id: &K) -> Result<&T, GraphError<K>> {
+        self.vertex_id(id).and_then(|v| self.value(v)).ok_or_else(|| GraphError::MissingVertex(id.clone()))
+    }
+
+    // see set_value. returns the old value
+    pub fn try_set_value(&mut self, id: &K, value: &T) -> Result<T, GraphError<K>> {
+        self.set_value(id, value).ok_or_else(|| GraphError::MissingVertex(id.clone()))
+    }
+
+    // see find_path
+    pub fn try_find_path(&self, src: &K, dst: &K) -> Result<Path<W, K>, GraphError<K>> {
+        self.edge_ends(src, dst)?;
+        GraphView::find_path(self, src, dst).ok_or_else(|| GraphError::Unreachable(src.clone(), dst.clone()))
+    }
+
+    // see shortest_path_tree
+    pub fn try_shortest_path_tree(&self, src: &K) -> Result<ShortestPathTree<W, K>, GraphError<K>> {
+        GraphView::shortest_path_tree(self, src).ok_or_else(|| GraphError::MissingSource(src.clone()))
+    }
+
+    // handles of src and dst, or which of them is missing
+    fn edge_ends(&self, src: &K, dst: &K) -> Result<(VertexId, VertexId), GraphError<K>> {
+        let u = self.vertex_id(src).ok_or_else(|| GraphError::MissingSource(src.clone()))?;
+        let v = self.vertex_id(dst).ok_or_else(|| GraphError::MissingDestination(dst.clone()))?;
+        Ok((u, v))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Graph, GraphError};
+
+    #[test]
+    fn typed_errors() {
+        let mut g: Graph<i32> = Graph::new();
+        let (a, b, c, x) = ("a".to_string(), "b".to_string(), "c".to_string(), "x".to_string());
+
+        let va = g.try_add_vertex(&a, &1).unwrap();
+        assert_eq!(g.vertex_id(&a), Some(va));
+        g.try_add_vertex(&b, &2).unwrap();
+        g.try_add_vertex(&c, &3).unwrap();
+        assert_eq!(g.try_add_vertex(&a, &9), Err(GraphError::DuplicateVertex(a.clone())));
+        assert_eq!(g.get_value(&a), Some(1));
+
+        assert_eq!(g.try_add_weighted_edge(&a, &b, 4), Ok(()));
+        assert_eq!(g.try_add_edge(&a, &b), Err(GraphError::DuplicateEdge(a.clone(), b.clone())));
+        assert_eq!(g.try_add_edge(&x, &b), Err(GraphError::MissingSource(x.clone())));
+        assert_eq!(g.try_add_edge(&a, &x), Err(GraphError::MissingDestination(x.clone())));
+
+        let path = g.try_find_path(&a, &b).unwrap();
+        assert_eq!(path.vertices, [a.clone(), b.clone()]);
+        assert_eq!(path.cost, 4);
+        assert_eq!(g.try_find_path(&a, &c).unwrap_err(), GraphError::Unreachable(a.clone(), c.clone()));
+        assert_eq!(g.try_find_path(&a, &x).unwrap_err(), GraphError::MissingDestination(x.clone()));
+        assert_eq!(g.try_shortest_path_tree(&x).unwrap_err(), GraphError::MissingSource(x.clone()));
+        assert_eq!(g.try_shortest_path_tree(&a).unwrap().distance(&b), Some(4));
+
+        assert_eq!(g.try_remove_edge(&a, &b), Ok(4));
+        assert_eq!(g.try_remove_edge(&a, &b), Err(GraphError::MissingEdge(a.clone(), b.clone())));
+        assert_eq!(g.try_get_value(&c), Ok(&3));
+        assert_eq!(g.try_set_value(&c, &5), Ok(3));
+        assert_eq!(g.try_set_value(&x, &5), Err(GraphError::MissingVertex(x.clone())));
+        assert_eq!(g.try_remove_vertex(&c), Ok(5));
+        assert_eq!(g.try_get_value(&c), Err(GraphError::MissingVertex(c.clone())));
+
+        assert_eq!(GraphError::MissingEdge(a.clone(), b.clone()).to_string(), "edge a->b does not exist");
+        assert_eq!(GraphError::Unreachable(a, c).to_string(), "c can not be reached from a");
+    }
+}
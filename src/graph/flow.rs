@@ -0,0 +1,349 @@
+use super::{Graph, VertexId, Weight};
+use std::collections::{HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Sub;
+
+// maximum flow from one vertex to another, see Graph::edmonds_karp and Graph::dinic
+#[derive(Debug, Clone)]
+pub struct MaxFlow<W, K = String> {
+    pub value: W,                // total flow out of the source
+    pub flows: Vec<(K, K, W)>,   // every edge with the flow through it. undirected edges are given in the direction of their flow
+    pub source_side: HashSet<K>, // source side of a minimum cut, the vertices still reachable from the source
+    pub cut: Vec<(K, K, W)>,     // edges from the source side to the sink side. their capacities add up to value
+}
+
+// residual network. arc a and arc a ^ 1 are each other's reverse
+struct Residual<W> {
+    head: Vec<VertexId>,    // vertex each arc points at
+    capacity: Vec<W>,       // capacity left on each arc
+    arcs: Vec<Vec<usize>>,  // arcs out of each vertex
+    edges: Vec<(usize, W)>, // graph edges as (first arc, capacity)
+}
+
+impl<W: Weight + Sub<Output = W>> Residual<W> {
+    // a directed edge is an arc with a reverse of zero capacity, an undirected edge is two arcs sharing the capacity
+    fn new<T, K, E>(g: &Graph<T, W, K, E>) -> Self
+    where
+        T: Default + Clone,
+        K: Hash + Eq + Clone,
+        E: Default + Clone,
+    {
+        let mut residual = Residual {
+            head: Vec::new(),
+            capacity: Vec::new(),
+            arcs: vec![Vec::new(); g.verts.slots()],
+            edges: Vec::new(),
+        };
+        for (u, v, w) in g.arcs().filter(|(u, v, _)| g.directed || u <= v) {
+            // negative capacities can't carry flow
+            let capacity = if w < W::zero() { W::zero() } else { w };
+            residual.edges.push((residual.head.len(), capacity));
+            residual.push_arc(u, v, capacity);
+            residual.push_arc(v, u, if g.directed { W::zero() } else { capacity });
+        }
+        residual
+    }
+
+    fn push_arc(&mut self, u: VertexId, v: VertexId, capacity: W) {
+        self.arcs[u.index()].push(self.head.len());
+        self.head.push(v);
+        self.capacity.push(capacity);
+    }
+
+    fn has_room(&self, a: usize) -> bool {
+        self.capacity[a] > W::zero()
+    }
+
+    // sends amount along arc a
+    fn push_flow(&mut self, a: usize, amount: W) {
+        self.capacity[a] = self.capacity[a] - amount;
+        self.capacity[a ^ 1] = self.capacity[a ^ 1].saturating_add(amount);
+    }
+
+    // breadth first distances in arcs from src over arcs with room left, None where unreachable
+    fn levels(&self, src: VertexId) -> Vec<Option<usize>> {
+        let mut level = vec![None; self.arcs.len()];
+        let mut queue = VecDeque::from([src]);
+        level[src.index()] = Some(0);
+
+        while let Some(u) = queue.pop_front() {
+            for &a in &self.arcs[u.index()] {
+                let v = self.head[a];
+                if self.has_room(a) && level[v.index()].is_none() {
+                    level[v.index()] = level[u.index()].map(|l| l + 1);
+                    queue.push_back(v);
+                }
+            }
+        }
+        level
+    }
+
+    // smallest capacity left along path
+    fn bottleneck(&self, path: &[usize]) -> W {
+        path.iter().map(|&a| self.capacity[a]).min_by(|a, b| a.weight_cmp(b)).unwrap()
+    }
+}
+
+impl<T, W, K, E> Graph<T, W, K, E>
+where
+    T: Default + Clone,
+    W: Weight + Sub<Output = W>,
+    K: Hash + Eq + Clone,
+    E: Default + Clone,
+{
+    // edmonds-karp algorithm. maximum flow from src to dst using the edge weights as capacities, augmenting along
+    // shortest paths in O(V*E^2). undirected edges carry flow either way up to their weight and negative weights
+    // carry nothing. returns None if either vertex is missing or they are the same vertex
+    pub fn edmonds_karp(&self, src: &K, dst: &K) -> Option<MaxFlow<W, K>> {
+        let (s, t) = self.flow_ends(src, dst)?;
+        let mut residual = Residual::new(self);
+        let mut value = W::zero();
+
+        loop {
+            // breadth first search for the shortest augmenting path, remembering the arc into each vertex
+            let mut into: Vec<Option<usize>> = vec![None; self.verts.slots()];
+            let mut queue = VecDeque::from([s]);
+            while let Some(u) = queue.pop_front() {
+                for &a in &residual.arcs[u.index()] {
+                    let v = residual.head[a];
+                    if residual.has_room(a) && v != s && into[v.index()].is_none() {
+                        into[v.index()] = Some(a);
+                        queue.push_back(v);
+                    }
+                }
+            }
+
+            let mut path = Vec::new();
+            let mut v = t;
+            while let Some(a) = into[v.index()] {
+                path.push(a);
+                v = residual.head[a ^ 1];
+            }
+            if path.is_empty() {
+                break;
+            }
+
+            let amount = residual.bottleneck(&path);
+            for &a in &path {
+                residual.push_flow(a, amount);
+            }
+            value = value.saturating_add(amount);
+        }
+        Some(self.max_flow(s, value, &residual))
+    }
+
+    // dinic's algorithm. same result as edmonds_karp in O(V^2*E), by sending a blocking flow through the
+    // breadth first levels of the residual network in each phase
+    pub fn dinic(&self, src: &K, dst: &K) -> Option<MaxFlow<W, K>> {
+        let (s, t) = self.flow_ends(src, dst)?;
+        let mut residual = Residual::new(self);
+        let mut value = W::zero();
+
+        loop {
+            let mut level = residual.levels(s);
+            if level[t.index()].is_none() {
+                break;
+            }
+
+            // iterative depth first search for paths that go one level down at each step.
+            // next[u] is the first arc out of u that may still lead to t in this phase
+            let mut next = vec![0; self.verts.slots()];
+            let mut path: Vec<usize> = Vec::new();
+            let mut u = s;
+            loop {
+                if u == t {
+                    let amount = residual.bottleneck(&path);
+                    for &a in &path {
+                        residual.push_flow(a, amount);
+                    }
+                    value = value.saturating_add(amount);
+
+                    // back up to the tail of the first arc that is now full
+                    let full = path.iter().position(|&a| !residual.has_room(a)).unwrap();
+                    path.truncate(full);
+                    u = path.last().map_or(s, |&a| residual.head[a]);
+                    continue;
+                }
+
+                let arcs = &residual.arcs[u.index()];
+                let admissible = |a: usize| {
+                    let v = residual.head[a];
+                    residual.has_room(a) && level[v.index()].is_some() && level[v.index()] == level[u.index()].map(|l| l + 1)
+                };
+                while next[u.index()] < arcs.len() && !admissible(arcs[next[u.index()]]) {
+                    next[u.index()] += 1;
+                }
+
+                if let Some(&a) = arcs.get(next[u.index()]) {
+                    path.push(a);
+                    u = residual.head[a];
+                } else if u == s {
+                    break;
+                } else {
+                    // dead end, nothing through u reaches t in this phase
+                    level[u.index()] = None;
+                    path.pop();
+                    u = path.last().map_or(s, |&a| residual.head[a]);
+                    next[u.index()] += 1;
+                }
+            }
+        }
+        Some(self.max_flow(s, value, &residual))
+    }
+
+    fn flow_ends(&self, src: &K, dst: &K) -> Option<(VertexId, VertexId)> {
+        let s = self.verts.id(src)?;
+        let t = self.verts.id(dst)?;
+        (s != t).then_some((s, t))
+    }
+
+    // reads the flows and a minimum cut out of the residual network left by a maximum flow
+    fn max_flow(&self, s: VertexId, value: W, residual: &Residual<W>) -> MaxFlow<W, K> {
+        let source_side: Vec<bool> = residual.levels(s).iter().map(Option::is_some).collect();
+        let key = |v: VertexId| self.verts.key(v).clone();
+        let mut flows = Vec::with_capacity(residual.edges.len());
+        let mut cut = Vec::new();
+
+        for &(a, capacity) in &residual.edges {
+            let (u, v) = (residual.head[a ^ 1], residual.head[a]);
+            // both arcs of an undirected edge start full, the flow goes along whichever one has lost capacity
+            if self.directed || residual.capacity[a] <= capacity {
+                flows.push((key(u), key(v), capacity - residual.capacity[a]));
+            } else {
+                flows.push((key(v), key(u), capacity - residual.capacity[a ^ 1]));
+            }
+
+            if source_side[u.index()] && !source_side[v.index()] {
+                cut.push((key(u), key(v), capacity));
+            } else if !self.directed && source_side[v.index()] && !source_side[u.index()] {
+                cut.push((key(v), key(u), capacity));
+            }
+        }
+
+        MaxFlow {
+            value,
+            flows,
+            source_side: self.verts.iter().filter(|(v, _)| source_side[v.index()]).map(|(_, vertex)| vertex.id.clone()).collect(),
+            cut,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Graph, MaxFlow};
+    use crate::graph::test_support::graph_from;
+    use std::collections::HashMap;
+
+    // every flow fits its edge and everything but src and dst passes on what it receives
+    fn check_flow(g: &Graph<i32>, flow: &MaxFlow<i32>, src: &str, dst: &str) {
+        let mut net: HashMap<&str, i32> = HashMap::new();
+        for (u, v, f) in &flow.flows {
+            let capacity = g.edge_weight(u, v).unwrap();
+            assert!(0 <= *f && *f <= capacity, "{}->{} carries {} of {}", u, v, f, capacity);
+            *net.entry(u).or_default() -= f;
+            *net.entry(v).or_default() += f;
+        }
+        for (id, _) in g.vertices() {
+            let expected = match id.as_str() {
+                _ if id == src => -flow.value,
+                _ if id == dst => flow.value,
+                _ => 0,
+            };
+            assert_eq!(net.get(id.as_str()).copied().unwrap_or(0), expected, "{}", id);
+        }
+        assert_eq!(flow.cut.iter().map(|(_, _, c)| c).sum::<i32>(), flow.value);
+        assert!(flow.source_side.contains(src));
+        assert!(!flow.source_side.contains(dst));
+    }
+
+    fn sorted_cut(flow: &MaxFlow<i32>) -> Vec<(String, String, i32)> {
+        let mut cut = flow.cut.clone();
+        cut.sort();
+        cut
+    }
+
+    #[test]
+    fn clrs_network() {
+        // figure 26.1 of cormen et al., maximum flow 23
+        let g = graph_from(
+            true,
+            &[],
+            &[
+                ("s", "v1", 16),
+                ("s", "v2", 13),
+                ("v2", "v1", 4),
+                ("v1", "v3", 12),
+                ("v3", "v2", 9),
+                ("v2", "v4", 14),
+                ("v4", "v3", 7),
+                ("v3", "t", 20),
+                ("v4", "t", 4),
+            ],
+        );
+        let (s, t) = ("s".to_string(), "t".to_string());
+
+        for flow in [g.edmonds_karp(&s, &t).unwrap(), g.dinic(&s, &t).unwrap()] {
+            assert_eq!(flow.value, 23);
+            assert_eq!(flow.flows.len(), 9);
+            check_flow(&g, &flow, "s", "t");
+            let mut source_side: Vec<&str> = flow.source_side.iter().map(String::as_str).collect();
+            source_side.sort();
+            assert_eq!(source_side, ["s", "v1", "v2", "v4"]);
+            assert_eq!(
+                sorted_cut(&flow),
+                [
+                    ("v1".to_string(), "v3".to_string(), 12),
+                    ("v4".to_string(), "t".to_string(), 4),
+                    ("v4".to_string(), "v3".to_string(), 7)
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn edmonds_karp_example() {
+        // the worked example from the wikipedia article on edmonds-karp, maximum flow 5
+        let g = graph_from(
+            true,
+            &[],
+            &[
+                ("a", "b", 3),
+                ("a", "d", 3),
+                ("b", "c", 4),
+                ("c", "a", 3),
+                ("c", "d", 1),
+                ("c", "e", 2),
+                ("d", "e", 2),
+                ("d", "f", 6),
+                ("e", "b", 1),
+                ("e", "g", 1),
+                ("f", "g", 9),
+            ],
+        );
+        let (a, g_) = ("a".to_string(), "g".to_string());
+        for flow in [g.edmonds_karp(&a, &g_).unwrap(), g.dinic(&a, &g_).unwrap()] {
+            assert_eq!(flow.value, 5);
+            check_flow(&g, &flow, "a", "g");
+        }
+
+        // the reverse direction is cut off by c->a alone
+        let back = g.dinic(&g_, &a).unwrap();
+        assert_eq!(back.value, 0);
+        assert!(back.cut.is_empty());
+        assert!(g.edmonds_karp(&a, &a).is_none());
+        assert!(g.dinic(&a, &"x".to_string()).is_none());
+    }
+
+    #[test]
+    fn undirected_flow() {
+        // flow has to go b->c against the order the edge was added in
+        let g = graph_from(false, &[], &[("s", "a", 3), ("s", "b", 2), ("c", "b", 4), ("a", "t", 1), ("c", "t", 5), ("a", "c", 1)]);
+        let (s, t) = ("s".to_string(), "t".to_string());
+        for flow in [g.edmonds_karp(&s, &t).unwrap(), g.dinic(&s, &t).unwrap()] {
+            assert_eq!(flow.value, 4);
+            check_flow(&g, &flow, "s", "t");
+            assert!(flow.flows.contains(&("b".to_string(), "c".to_string(), 2)));
+        }
+    }
+}
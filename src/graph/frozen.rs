@@ -0,0 +1,202 @@
+use super::vertex::VertexTable;
+use super::{Graph, GraphView, VertexId, Weight};
+use std::hash::Hash;
+
+// immutable graph in compressed sparse row form, made by Graph::freeze for graphs that are built once and queried
+// many times. the out edges of every vertex sit next to each other in two flat arrays, so walking them is a slice
+// scan instead of a HashMap iteration. vertices keep the VertexIds they had in the Graph.
+// queries are provided by GraphView
+#[derive(Debug, Clone)]
+pub struct FrozenGraph<T, W = i32, K = String> {
+    verts: VertexTable<K, T>,
+    offsets: Vec<usize>,    // the out edges of v are at offsets[v]..offsets[v + 1] in targets and weights
+    targets: Vec<VertexId>, // destination of each edge, sorted within each vertex
+    weights: Vec<W>,        // weight of each edge
+    edge_count: usize,
+    directed: bool,
+}
+
+impl<T, W, K, E> Graph<T, W, K, E>
+where
+    T: Default + Clone,
+    W: Weight,
+    K: Hash + Eq + Clone,
+    E: Default + Clone,
+{
+    // an immutable copy of the graph in compressed sparse row form. see FrozenGraph. edge payloads are not copied
+    pub fn freeze(&self) -> FrozenGraph<T, W, K> {
+        let mut offsets = Vec::with_capacity(self.id_bound() + 1);
+        let mut targets = Vec::new();
+        let mut weights = Vec::new();
+        let mut row: Vec<(VertexId, W)> = Vec::new();
+
+        offsets.push(0);
+        for adjacency_list in &self.adjacency_lists {
+            row.clear();
+            row.extend(adjacency_list.iter().map(|(&v, edge)| (v, edge.weight)));
+            row.sort_unstable_by_key(|&(v, _)| v);
+            for &(v, w) in &row {
+                targets.push(v);
+                weights.push(w);
+            }
+            offsets.push(targets.len());
+        }
+
+        FrozenGraph {
+            verts: self.verts.clone(),
+            offsets,
+            targets,
+            weights,
+            edge_count: self.edge_count,
+            directed: self.directed,
+        }
+    }
+}
+
+impl<T, W, K> FrozenGraph<T, W, K>
+where
+    W: Weight,
+{
+    // positions of the out edges of v in targets and weights, empty if v is not in the graph
+    fn row(&self, v: VertexId) -> std::ops::Range<usize> {
+        match self.offsets.get(v.index() + 1) {
+            Some(&end) => self.offsets[v.index()]..end,
+            None => 0..0,
+        }
+    }
+}
+
+impl<T, W, K> GraphView for FrozenGraph<T, W, K>
+where
+    W: Weight,
+    K: Hash + Eq + Clone,
+    T: Clone,
+{
+    type Key = K;
+    type Value = T;
+    type Weight = W;
+
+    fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    fn vertex_count(&self) -> usize {
+        self.verts.len()
+    }
+
+    fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    fn id_bound(&self) -> usize {
+        self.verts.slots()
+    }
+
+    fn vertex_id(&self, id: &K) -> Option<VertexId> {
+        self.verts.id(id)
+    }
+
+    fn vertex_key(&self, v: VertexId) -> Option<&K> {
+        self.verts.get(v).map(|vertex| &vertex.id)
+    }
+
+    fn value(&self, v: VertexId) -> Option<&T> {
+        self.verts.get(v).map(|vertex| &vertex.value)
+    }
+
+    fn vertex_ids(&self) -> impl Iterator<Item = VertexId> + '_ {
+        self.verts.ids()
+    }
+
+    fn out_edges(&self, v: VertexId) -> impl Iterator<Item = (VertexId, W)> + '_ {
+        let row = self.row(v);
+        self.targets[row.clone()].iter().copied().zip(self.weights[row].iter().copied())
+    }
+
+    // binary search within the sorted out edges of src
+    fn edge_weight_by_id(&self, src: VertexId, dst: VertexId) -> Option<W> {
+        let row = self.row(src);
+        let i = self.targets[row.clone()].binary_search(&dst).ok()?;
+        Some(self.weights[row.start + i])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Graph, GraphView};
+    use crate::graph::test_support::graph_from;
+
+    #[test]
+    fn frozen_queries_match_graph() {
+        let edges = [
+            ("a", "b", 7),
+            ("a", "c", 9),
+            ("a", "f", 14),
+            ("b", "c", 10),
+            ("b", "d", 15),
+            ("c", "d", 11),
+            ("c", "f", 2),
+            ("d", "e", 6),
+            ("f", "e", 9),
+        ];
+        let g = graph_from(true, &[], &edges);
+        let frozen = g.freeze();
+        let (a, e) = ("a".to_string(), "e".to_string());
+
+        assert_eq!(frozen.vertex_count(), 6);
+        assert_eq!(frozen.edge_count(), 9);
+        for (src, dst, w) in edges {
+            let (src, dst) = (frozen.vertex_id(&src.to_string()).unwrap(), frozen.vertex_id(&dst.to_string()).unwrap());
+            assert_eq!(frozen.edge_weight_by_id(src, dst), Some(w));
+            assert!(!frozen.has_edge_by_id(dst, src));
+        }
+
+        assert_eq!(frozen.shortest_path(&a, &e), g.shortest_path(&a, &e));
+        assert_eq!(frozen.shortest_path_tree(&a), g.shortest_path_tree(&a));
+        assert_eq!(frozen.bellman_ford(&a), g.bellman_ford(&a));
+        assert_eq!(frozen.find_path(&a, &e).unwrap().cost, 20);
+        assert_eq!(frozen.search(&a, &e), Some(e.clone()));
+        assert_eq!(frozen.search(&e, &a), None);
+        assert_eq!(frozen.shortest_path(&a, &"foo".to_string()).len(), 0);
+    }
+
+    #[test]
+    fn frozen_keeps_vertex_ids() {
+        let mut g = graph_from(true, &[], &[("a", "b", 1), ("b", "c", 1), ("c", "d", 1)]);
+        g.add_weighted_edge(&"a".to_string(), &"d".to_string(), 5);
+        let b = g.vertex_id(&"b".to_string()).unwrap();
+        g.remove_vertex(&"b".to_string());
+        let a = g.vertex_id(&"a".to_string()).unwrap();
+        let d = g.vertex_id(&"d".to_string()).unwrap();
+
+        let frozen = g.freeze();
+        assert_eq!(frozen.vertex_count(), 3);
+        assert_eq!(frozen.edge_count(), 2);
+        assert_eq!(frozen.vertex_id(&"d".to_string()), Some(d));
+        assert_eq!(frozen.vertex_key(b), None);
+        assert_eq!(frozen.out_edges(b).count(), 0);
+        assert_eq!(frozen.shortest_path_by_id(a, d), [a, d]);
+
+        // the frozen copy does not see later changes
+        g.add_weighted_edge(&"d".to_string(), &"a".to_string(), 1);
+        assert!(!frozen.has_edge_by_id(d, a));
+    }
+
+    #[test]
+    fn frozen_undirected() {
+        let mut g: Graph<(i32, i32)> = Graph::new_undirected();
+        for (id, pos) in [("a", (0, 0)), ("b", (1, 0)), ("c", (2, 0))] {
+            g.add_vertex(&id.to_string(), &pos);
+        }
+        g.add_edge(&"b".to_string(), &"a".to_string());
+        g.add_edge(&"c".to_string(), &"b".to_string());
+
+        let frozen = g.freeze();
+        assert!(!frozen.is_directed());
+        assert_eq!(frozen.edge_count(), 2);
+        let manhattan = |a: &(i32, i32), b: &(i32, i32)| (a.0 - b.0).abs() + (a.1 - b.1).abs();
+        let path = frozen.astar(&"a".to_string(), &"c".to_string(), manhattan).unwrap();
+        assert_eq!(path.vertices, ["a", "b", "c"]);
+        assert_eq!(frozen.shortest_path(&"c".to_string(), &"a".to_string()), ["c", "b", "a"]);
+    }
+}
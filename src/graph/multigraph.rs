@@ -0,0 +1,345 @@
+use super::shortest_path::dijkstra;
+use super::vertex::VertexTable;
+use super::{ShortestPathTree, VertexId, Weight};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+// stable handle for an edge of a MultiGraph. ids are never reused, even after the edge is removed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EdgeId(u64);
+
+// an edge of a MultiGraph, with its own weight and payload. see MultiGraph::edge_ends for the keys of its ends
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiEdge<W, E> {
+    ends: (VertexId, VertexId), // handles of src and dst
+    weight: W,
+    payload: E,
+}
+
+impl<W, E> MultiEdge<W, E>
+where
+    W: Weight,
+{
+    // handles of src and dst
+    pub fn ends(&self) -> (VertexId, VertexId) {
+        self.ends
+    }
+
+    pub fn weight(&self) -> W {
+        self.weight
+    }
+
+    pub fn payload(&self) -> &E {
+        &self.payload
+    }
+}
+
+// directed graph that allows any number of parallel edges between two vertices, for example several
+// transport links with different costs. each edge is addressed by its EdgeId and carries a weight and a payload.
+// vertices are identified by String keys unless another Hash + Eq key type is given, and by their VertexId handles
+#[derive(Debug)]
+pub struct MultiGraph<T, W = i32, K = String, E = ()> {
+    verts: VertexTable<K, T>,                             // vertex id to handle and handle to vertex
+    edges: HashMap<EdgeId, MultiEdge<W, E>>,              // edge id to edge
+    adjacency_lists: Vec<HashMap<VertexId, Vec<EdgeId>>>, // src to dst to the ids of every edge src->dst
+    in_adjacency_lists: Vec<HashSet<VertexId>>,           // dst to every src with an edge src->dst
+    next_edge_id: u64,
+}
+
+impl<T, W, K, E> MultiGraph<T, W, K, E>
+where
+    T: Default + Clone,
+    W: Weight,
+    K: Hash + Eq + Clone,
+{
+    pub fn new() -> MultiGraph<T, W, K, E> {
+        MultiGraph {
+            verts: VertexTable::new(),
+            edges: HashMap::new(),
+            adjacency_lists: Vec::new(),
+            in_adjacency_lists: Vec::new(),
+            next_edge_id: 0,
+        }
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.verts.len()
+    }
+
+    // number of edges, counting every parallel edge
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    // adds the vertex x, if it is not there
+    pub fn add_vertex(&mut self, id: &K, value: &T) -> bool {
+        let (_, added) = self.verts.insert(id, value);
+        if added {
+            self.adjacency_lists.push(HashMap::new());
+            self.in_adjacency_lists.push(HashSet::new());
+        }
+        added
+    }
+
+    // handle of the vertex x
+    pub fn vertex_id(&self, id: &K) -> Option<VertexId> {
+        self.verts.id(id)
+    }
+
+    // removes the vertex x and all of its edges
+    pub fn remove_vertex(&mut self, id: &K) -> Option<T> {
+        self.remove_vertex_by_id(self.verts.id(id)?)
+    }
+
+    pub fn remove_vertex_by_id(&mut self, v: VertexId) -> Option<T> {
+        let vertex = self.verts.remove(v)?;
+
+        // only the edges into and out of v are visited
+        for (u, ids) in std::mem::take(&mut self.adjacency_lists[v.index()]) {
+            self.in_adjacency_lists[u.index()].remove(&v);
+            for id in ids {
+                self.edges.remove(&id);
+            }
+        }
+        for u in std::mem::take(&mut self.in_adjacency_lists[v.index()]) {
+            for id in self.adjacency_lists[u.index()].remove(&v).unwrap_or_default() {
+                self.edges.remove(&id);
+            }
+        }
+        Some(vertex.value)
+    }
+
+    //  returns the value associated with the vertex x
+    pub fn get_value(&self, id: &K) -> Option<T> {
+        let v = self.verts.id(id)?;
+        self.verts.get(v).map(|v| v.value.clone())
+    }
+
+    // key of the vertex with handle v
+    pub fn vertex_key(&self, v: VertexId) -> Option<&K> {
+        self.verts.get(v).map(|vertex| &vertex.id)
+    }
+
+    // adds a new edge src->dst, even if there already are edges between them.
+    // returns None if either vertex is missing
+    pub fn add_edge(&mut self, src: &K, dst: &K, weight: W, payload: E) -> Option<EdgeId> {
+        self.add_edge_by_id(self.verts.id(src)?, self.verts.id(dst)?, weight, payload)
+    }
+
+    pub fn add_edge_by_id(&mut self, src: VertexId, dst: VertexId, weight: W, payload: E) -> Option<EdgeId> {
+        if !self.verts.contains(src) || !self.verts.contains(dst) {
+            return None;
+        }
+        let ends = (src, dst);
+
+        let id = EdgeId(self.next_edge_id);
+        self.next_edge_id += 1;
+        self.edges.insert(id, MultiEdge { ends, weight, payload });
+        self.adjacency_lists[ends.0.index()].entry(ends.1).or_default().push(id);
+        self.in_adjacency_lists[ends.1.index()].insert(ends.0);
+        Some(id)
+    }
+
+    // removes a single edge, leaving any parallel edges in place
+    pub fn remove_edge(&mut self, id: EdgeId) -> Option<MultiEdge<W, E>> {
+        let e = self.edges.remove(&id)?;
+
+        let adjacency_list = &mut self.adjacency_lists[e.ends.0.index()];
+        let parallel = adjacency_list.get_mut(&e.ends.1).unwrap();
+        parallel.retain(|&x| x != id);
+        if parallel.is_empty() {
+            adjacency_list.remove(&e.ends.1);
+            self.in_adjacency_lists[e.ends.1.index()].remove(&e.ends.0);
+        }
+        Some(e)
+    }
+
+    pub fn edge(&self, id: EdgeId) -> Option<&MultiEdge<W, E>> {
+        self.edges.get(&id)
+    }
+
+    // keys of the src and dst of an edge
+    pub fn edge_ends(&self, id: EdgeId) -> Option<(&K, &K)> {
+        let (src, dst) = self.edges.get(&id)?.ends;
+        Some((self.verts.key(src), self.verts.key(dst)))
+    }
+
+    // every edge src->dst, in the order they were added
+    pub fn edges_between(&self, src: &K, dst: &K) -> impl Iterator<Item = (EdgeId, &MultiEdge<W, E>)> {
+        let ends = self.verts.id(src).zip(self.verts.id(dst));
+        ends.into_iter().flat_map(|(src, dst)| self.edges_between_by_id(src, dst))
+    }
+
+    pub fn edges_between_by_id(&self, src: VertexId, dst: VertexId) -> impl Iterator<Item = (EdgeId, &MultiEdge<W, E>)> {
+        let ids = self.adjacency_lists.get(src.index()).and_then(|adjacency_list| adjacency_list.get(&dst));
+        ids.into_iter().flatten().map(|&id| (id, &self.edges[&id]))
+    }
+
+    // the lightest edge src->dst. ties go to the edge added first
+    pub fn cheapest_edge(&self, src: &K, dst: &K) -> Option<(EdgeId, &MultiEdge<W, E>)> {
+        self.edges_between(src, dst).reduce(|best, e| if e.1.weight < best.1.weight { e } else { best })
+    }
+
+    // checks if there is at least one edge src->dst
+    pub fn has_edge(&self, src: &K, dst: &K) -> bool {
+        self.edges_between(src, dst).next().is_some()
+    }
+
+    // dijkstra from src. between two vertices only the cheapest parallel edge is considered.
+    // returns None if src is not in the graph
+    pub fn shortest_path_tree(&self, src: &K) -> Option<ShortestPathTree<W, K>> {
+        let src = self.verts.id(src)?;
+
+        let search = dijkstra(self.verts.slots(), src, |u| {
+            self.adjacency_lists[u.index()].iter().map(|(&v, ids)| {
+                let w = ids.iter().map(|id| self.edges[id].weight).reduce(|a, b| if b < a { b } else { a });
+                (v, w.unwrap())
+            })
+        });
+        Some(search.into_tree(src, |v| self.verts.key(v).clone()))
+    }
+
+    // shortest path between two vertices as a list of vertex ids, empty if there is no path
+    pub fn shortest_path(&self, src: &K, dst: &K) -> Vec<K> {
+        self.shortest_path_tree(src).and_then(|tree| tree.path_to(dst)).unwrap_or_default()
+    }
+
+    // the edges taken by shortest_path, using the cheapest edge between each pair of vertices
+    pub fn shortest_path_edges(&self, src: &K, dst: &K) -> Vec<EdgeId> {
+        let path = self.shortest_path(src, dst);
+        path.windows(2).map(|hop| self.cheapest_edge(&hop[0], &hop[1]).unwrap().0).collect()
+    }
+}
+
+impl<T, W, K, E> Default for MultiGraph<T, W, K, E>
+where
+    T: Default + Clone,
+    W: Weight,
+    K: Hash + Eq + Clone,
+{
+    fn default() -> Self {
+        MultiGraph::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MultiGraph;
+
+    fn transport() -> MultiGraph<i32, i32, String, &'static str> {
+        let mut g: MultiGraph<i32, i32, String, &'static str> = MultiGraph::new();
+        for id in ["a", "b", "c"] {
+            g.add_vertex(&id.to_string(), &0);
+        }
+        g
+    }
+
+    #[test]
+    fn parallel_edges() {
+        let mut g = transport();
+        let a = "a".to_string();
+        let b = "b".to_string();
+
+        let rail = g.add_edge(&a, &b, 5, "rail").unwrap();
+        let road = g.add_edge(&a, &b, 3, "road").unwrap();
+        let air = g.add_edge(&a, &b, 3, "air").unwrap();
+        assert_ne!(rail, road);
+        assert_eq!(g.edge_count(), 3);
+        assert_eq!(g.add_edge(&a, &"foo".to_string(), 1, "none"), None);
+
+        let between: Vec<_> = g.edges_between(&a, &b).map(|(id, e)| (id, *e.payload())).collect();
+        assert_eq!(between, [(rail, "rail"), (road, "road"), (air, "air")]);
+        assert_eq!(g.edges_between(&b, &a).count(), 0);
+
+        let (id, e) = g.cheapest_edge(&a, &b).unwrap();
+        assert_eq!(id, road);
+        assert_eq!(e.weight(), 3);
+        assert_eq!(g.edge_ends(id), Some((&a, &b)));
+        assert_eq!(g.vertex_key(e.ends().0), Some(&a));
+
+        // removing one edge keeps the others and their ids
+        assert_eq!(g.remove_edge(road).unwrap().payload(), &"road");
+        assert_eq!(g.remove_edge(road), None);
+        assert_eq!(g.cheapest_edge(&a, &b).unwrap().0, air);
+        assert_eq!(g.edge(rail).unwrap().payload(), &"rail");
+        assert!(g.has_edge(&a, &b));
+
+        g.remove_edge(rail);
+        g.remove_edge(air);
+        assert!(!g.has_edge(&a, &b));
+
+        // ids are not reused
+        assert!(g.add_edge(&a, &b, 1, "ferry").unwrap() > air);
+    }
+
+    #[test]
+    fn remove_vertex() {
+        let mut g = transport();
+        let a = "a".to_string();
+        let b = "b".to_string();
+        let c = "c".to_string();
+        g.add_edge(&a, &b, 1, "x");
+        g.add_edge(&b, &a, 1, "y");
+        g.add_edge(&b, &b, 1, "loop");
+        let kept = g.add_edge(&a, &c, 1, "z").unwrap();
+        let removed = g.add_edge(&c, &b, 1, "w").unwrap();
+        g.remove_edge(removed);
+
+        assert_eq!(g.remove_vertex(&b), Some(0));
+        assert_eq!(g.remove_vertex(&b), None);
+        assert_eq!(g.edge_count(), 1);
+        assert!(g.edge(kept).is_some());
+        assert!(!g.has_edge(&a, &b));
+
+        // b can come back without any of its old edges
+        g.add_vertex(&b, &1);
+        assert!(!g.has_edge(&a, &b) && !g.has_edge(&b, &a));
+        assert_eq!(g.edge_ends(kept), Some((&a, &c)));
+    }
+
+    #[test]
+    fn keys_and_handles() {
+        let mut g: MultiGraph<(), u32, u32> = MultiGraph::new();
+        for stop in [10, 20, 30] {
+            g.add_vertex(&stop, &());
+        }
+        let (x, y) = (g.vertex_id(&10).unwrap(), g.vertex_id(&20).unwrap());
+        let bus = g.add_edge_by_id(x, y, 4, ()).unwrap();
+        let tram = g.add_edge_by_id(x, y, 2, ()).unwrap();
+        g.add_edge(&20, &30, 1, ());
+
+        let between: Vec<_> = g.edges_between_by_id(x, y).map(|(id, _)| id).collect();
+        assert_eq!(between, [bus, tram]);
+        assert_eq!(g.edge_ends(tram), Some((&10, &20)));
+        assert_eq!(g.shortest_path(&10, &30), [10, 20, 30]);
+
+        assert_eq!(g.remove_vertex_by_id(y), Some(()));
+        assert_eq!(g.add_edge_by_id(x, y, 1, ()), None);
+        assert_eq!(g.edges_between_by_id(x, y).count(), 0);
+        assert_eq!(g.edge_count(), 0);
+    }
+
+    #[test]
+    fn shortest_path_uses_cheapest_parallel_edge() {
+        let mut g = transport();
+        let a = "a".to_string();
+        let b = "b".to_string();
+        let c = "c".to_string();
+
+        g.add_edge(&a, &c, 10, "direct");
+        g.add_edge(&a, &b, 8, "slow");
+        let fast = g.add_edge(&a, &b, 2, "fast").unwrap();
+        let last = g.add_edge(&b, &c, 3, "last").unwrap();
+        g.add_edge(&b, &c, 9, "detour");
+
+        let tree = g.shortest_path_tree(&a).unwrap();
+        assert_eq!(tree.distance(&b), Some(2));
+        assert_eq!(tree.distance(&c), Some(5));
+        assert_eq!(g.shortest_path(&a, &c), ["a", "b", "c"]);
+        assert_eq!(g.shortest_path_edges(&a, &c), [fast, last]);
+
+        assert_eq!(g.shortest_path(&c, &a).len(), 0);
+        assert_eq!(g.shortest_path_edges(&c, &a).len(), 0);
+        assert_eq!(g.shortest_path_tree(&"foo".to_string()), None);
+    }
+}
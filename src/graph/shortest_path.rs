@@ -0,0 +1,677 @@
+use super::view::arcs;
+use super::{Graph, GraphView, VertexId, Weight};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt;
+use std::hash::Hash;
+
+// result of a single source shortest path search.
+// holds the distance to every reachable vertex and the predecessor of each vertex on its shortest path,
+// so paths to any target can be rebuilt without searching again.
+// vertices are identified by their keys, or by their VertexIds for the *_by_id searches
+#[derive(Debug, Clone)]
+pub struct ShortestPathTree<W, K = String> {
+    source: K,
+    dist: HashMap<K, W>, // vertex to distance from the source. unreachable vertices are absent
+    prev: HashMap<K, K>, // vertex to the previous hop on the shortest path from the source
+}
+
+impl<W, K> ShortestPathTree<W, K>
+where
+    W: Weight,
+    K: Hash + Eq + Clone,
+{
+    // the vertex the search started from
+    pub fn source(&self) -> &K {
+        &self.source
+    }
+
+    // distance from the source to the vertex x, if x is reachable
+    pub fn distance(&self, id: &K) -> Option<W> {
+        self.dist.get(id).copied()
+    }
+
+    // the vertex before x on the shortest path from the source. the source has no predecessor
+    pub fn predecessor(&self, id: &K) -> Option<&K> {
+        self.prev.get(id)
+    }
+
+    pub fn is_reachable(&self, id: &K) -> bool {
+        self.dist.contains_key(id)
+    }
+
+    // all reachable vertices and their distances from the source
+    pub fn distances(&self) -> &HashMap<K, W> {
+        &self.dist
+    }
+
+    // all reachable vertices other than the source and their predecessors
+    pub fn predecessors(&self) -> &HashMap<K, K> {
+        &self.prev
+    }
+
+    // rebuilds the path source->..->x by walking the predecessor map back from x
+    pub fn path_to(&self, id: &K) -> Option<Vec<K>> {
+        if !self.is_reachable(id) {
+            return None;
+        }
+
+        let mut path = vec![id.clone()];
+        let mut u = id;
+        while let Some(p) = self.prev.get(u) {
+            path.push(p.clone());
+            u = p;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+impl<W, K> PartialEq for ShortestPathTree<W, K>
+where
+    W: PartialEq,
+    K: Hash + Eq,
+{
+    fn eq(&self, rhs: &Self) -> bool {
+        self.source == rhs.source && self.dist == rhs.dist && self.prev == rhs.prev
+    }
+}
+
+// a single path found by a point to point search, see Graph::find_path and Graph::astar.
+// both return this type so one can be swapped for the other
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path<W, K = String> {
+    pub vertices: Vec<K>, // vertices from the source to the destination, inclusive
+    pub cost: W,          // sum of the edge weights along the path
+    pub expanded: usize,  // number of vertices the search settled, counting the destination
+}
+
+// error returned by bellman_ford when a cycle with a negative total weight can be reached from the source.
+// shortest paths through such a cycle are unbounded
+#[derive(Debug, Clone, PartialEq)]
+pub struct NegativeCycle<K = String> {
+    cycle: Vec<K>, // vertices of the cycle in edge order, the last vertex has an edge back to the first
+}
+
+impl<K> NegativeCycle<K> {
+    pub fn cycle(&self) -> &[K] {
+        &self.cycle
+    }
+
+    // the same cycle with each vertex replaced by f(vertex)
+    pub(super) fn map<F, J>(self, f: F) -> NegativeCycle<J>
+    where
+        F: FnMut(K) -> J,
+    {
+        NegativeCycle {
+            cycle: self.cycle.into_iter().map(f).collect(),
+        }
+    }
+}
+
+impl<K> fmt::Display for NegativeCycle<K>
+where
+    K: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "negative cycle ")?;
+        for id in &self.cycle {
+            write!(f, "{}->", id)?;
+        }
+        match self.cycle.first() {
+            Some(first) => write!(f, "{}", first),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<K> std::error::Error for NegativeCycle<K> where K: fmt::Debug + fmt::Display {}
+
+// distances and predecessors found by a single source search, indexed by VertexId
+#[derive(Debug)]
+pub(super) struct Search<W> {
+    pub(super) dist: Vec<Option<W>>,
+    pub(super) prev: Vec<Option<VertexId>>,
+}
+
+impl<W> Search<W>
+where
+    W: Weight,
+{
+    // only src is reached, at a distance of zero. slots is the number of handles the graph has given out
+    pub(super) fn new(slots: usize, src: VertexId) -> Self {
+        let mut dist = vec![None; slots];
+        dist[src.index()] = Some(W::zero());
+        Search { dist, prev: vec![None; slots] }
+    }
+
+    // path from the source to a reached vertex
+    pub(super) fn path_to(&self, id: VertexId) -> Vec<VertexId> {
+        let mut path = vec![id];
+        let mut u = id;
+        while let Some(p) = self.prev[u.index()] {
+            path.push(p);
+            u = p;
+        }
+        path.reverse();
+        path
+    }
+
+    // the search as a ShortestPathTree, with each vertex identified by key(handle)
+    pub(super) fn into_tree<K, F>(self, src: VertexId, key: F) -> ShortestPathTree<W, K>
+    where
+        K: Hash + Eq + Clone,
+        F: Fn(VertexId) -> K,
+    {
+        let reached = || (0..self.dist.len()).filter(|&i| self.dist[i].is_some());
+        let dist = reached().map(|i| (key(VertexId::new(i)), self.dist[i].unwrap())).collect();
+        let prev = reached().filter_map(|i| Some((key(VertexId::new(i)), key(self.prev[i]?)))).collect();
+        ShortestPathTree { source: key(src), dist, prev }
+    }
+}
+
+// entry in the dijkstra priority queue. ordered so that BinaryHeap (a max heap) pops the smallest distance first.
+// for a* dist includes the heuristic, and ties go to the entry furthest from the source (largest cost) since it is
+// likely closer to the goal. remaining ties are broken on the vertex handle to keep the search deterministic
+#[derive(Debug)]
+struct QueueEntry<W> {
+    dist: W,
+    cost: W,
+    id: VertexId,
+}
+
+impl<W: Weight> Ord for QueueEntry<W> {
+    fn cmp(&self, rhs: &Self) -> Ordering {
+        rhs.dist
+            .weight_cmp(&self.dist)
+            .then_with(|| self.cost.weight_cmp(&rhs.cost))
+            .then_with(|| rhs.id.cmp(&self.id))
+    }
+}
+
+impl<W: Weight> PartialOrd for QueueEntry<W> {
+    fn partial_cmp(&self, rhs: &Self) -> Option<Ordering> {
+        Some(self.cmp(rhs))
+    }
+}
+
+impl<W: Weight> PartialEq for QueueEntry<W> {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.cmp(rhs) == Ordering::Equal
+    }
+}
+
+impl<W: Weight> Eq for QueueEntry<W> {}
+
+impl<T, W, K, E> Graph<T, W, K, E>
+where
+    T: Default + Clone,
+    W: Weight,
+    K: Hash + Eq + Clone,
+    E: Default + Clone,
+{
+    // see GraphView::shortest_path_tree
+    pub fn shortest_path_tree(&self, src: &K) -> Option<ShortestPathTree<W, K>> {
+        GraphView::shortest_path_tree(self, src)
+    }
+
+    pub fn shortest_path_tree_by_id(&self, src: VertexId) -> Option<ShortestPathTree<W, VertexId>> {
+        GraphView::shortest_path_tree_by_id(self, src)
+    }
+
+    // see GraphView::bellman_ford
+    pub fn bellman_ford(&self, src: &K) -> Option<Result<ShortestPathTree<W, K>, NegativeCycle<K>>> {
+        GraphView::bellman_ford(self, src)
+    }
+
+    // see GraphView::find_path
+    pub fn find_path(&self, src: &K, dst: &K) -> Option<Path<W, K>> {
+        GraphView::find_path(self, src, dst)
+    }
+
+    pub fn find_path_by_id(&self, src: VertexId, dst: VertexId) -> Option<Path<W, VertexId>> {
+        GraphView::find_path_by_id(self, src, dst)
+    }
+
+    // see GraphView::astar
+    pub fn astar<H>(&self, src: &K, dst: &K, heuristic: H) -> Option<Path<W, K>>
+    where
+        H: Fn(&T, &T) -> W,
+    {
+        GraphView::astar(self, src, dst, heuristic)
+    }
+}
+
+// dijkstra from src where cost(x, y, w) gives the cost used for the edge x->y of weight w, or None to skip the edge.
+// src must be in the graph
+pub(super) fn dijkstra_by<G, F>(g: &G, src: VertexId, cost: F) -> Search<G::Weight>
+where
+    G: GraphView + ?Sized,
+    F: Fn(VertexId, VertexId, G::Weight) -> Option<G::Weight>,
+{
+    let cost = &cost;
+    dijkstra(g.id_bound(), src, |u| g.out_edges(u).filter_map(move |(v, w)| Some((v, cost(u, v, w)?))))
+}
+
+// bellman-ford from src, which must be in the graph
+pub(super) fn bellman_ford<G>(g: &G, src: VertexId) -> Result<Search<G::Weight>, NegativeCycle<VertexId>>
+where
+    G: GraphView + ?Sized,
+{
+    let mut search: Search<G::Weight> = Search::new(g.id_bound(), src);
+
+    // after i passes every shortest path of at most i edges has been found. a simple path has at most V-1 edges,
+    // so if an edge can still be relaxed on pass V there is a negative cycle
+    let mut relaxed: Option<VertexId> = None;
+    for _ in 0..g.vertex_count() {
+        relaxed = None;
+        for (x, y, w) in arcs(g) {
+            let Some(d) = search.dist[x.index()] else {
+                continue;
+            };
+            let Some(alt) = d.checked_add(w) else {
+                continue;
+            };
+            if alt.is_infinity() {
+                continue;
+            }
+            if search.dist[y.index()].is_none_or(|current| alt < current) {
+                search.dist[y.index()] = Some(alt);
+                search.prev[y.index()] = Some(x);
+                relaxed = Some(y);
+            }
+        }
+        if relaxed.is_none() {
+            break;
+        }
+    }
+
+    match relaxed {
+        None => Ok(search),
+        Some(v) => Err(negative_cycle(&search.prev, v, g.vertex_count())),
+    }
+}
+
+// a* from src to dst, which must both be in the graph. estimate(x) is the heuristic for the vertex x
+pub(super) fn astar<G, H>(g: &G, src: VertexId, dst: VertexId, estimate: H) -> Option<Path<G::Weight, VertexId>>
+where
+    G: GraphView + ?Sized,
+    H: Fn(VertexId) -> G::Weight,
+{
+    // dist[u] is the cost of the best known path from src to u
+    let mut search: Search<G::Weight> = Search::new(g.id_bound(), src);
+    let mut settled = vec![false; g.id_bound()];
+    let mut expanded = 0;
+    // ordered by dist[u] + heuristic(u)
+    let mut queue = BinaryHeap::new();
+    queue.push(QueueEntry {
+        dist: estimate(src),
+        cost: G::Weight::zero(),
+        id: src,
+    });
+
+    while let Some(QueueEntry { id: u, .. }) = queue.pop() {
+        if settled[u.index()] {
+            continue;
+        }
+        settled[u.index()] = true;
+        expanded += 1;
+
+        let d = search.dist[u.index()].unwrap();
+        if u == dst {
+            return Some(Path {
+                vertices: search.path_to(dst),
+                cost: d,
+                expanded,
+            });
+        }
+
+        for (v, w) in g.out_edges(u) {
+            if settled[v.index()] {
+                continue;
+            }
+            let Some(alt) = d.checked_add(w) else {
+                continue;
+            };
+            if alt.is_infinity() {
+                continue;
+            }
+            if search.dist[v.index()].is_none_or(|current| alt < current) {
+                search.dist[v.index()] = Some(alt);
+                search.prev[v.index()] = Some(u);
+                queue.push(QueueEntry {
+                    dist: alt.saturating_add(estimate(v)),
+                    cost: alt,
+                    id: v,
+                });
+            }
+        }
+    }
+    None
+}
+
+// dijkstra's algorithm from src. successors(x) gives (y, cost) for every edge x->y, so graph types with
+// different storage can share the search. slots is the number of handles the graph has given out
+pub(super) fn dijkstra<W, F, I>(slots: usize, src: VertexId, successors: F) -> Search<W>
+where
+    W: Weight,
+    F: Fn(VertexId) -> I,
+    I: Iterator<Item = (VertexId, W)>,
+{
+    let mut search = Search::new(slots, src);
+    let mut settled = vec![false; slots];
+    let mut queue: BinaryHeap<QueueEntry<W>> = BinaryHeap::new();
+    queue.push(QueueEntry {
+        dist: W::zero(),
+        cost: W::zero(),
+        id: src,
+    });
+
+    while let Some(QueueEntry { dist: d, id: u, .. }) = queue.pop() {
+        // stale entry, u was already reached through a shorter path
+        if settled[u.index()] {
+            continue;
+        }
+        settled[u.index()] = true;
+
+        for (v, w) in successors(u) {
+            if settled[v.index()] {
+                continue;
+            }
+            let Some(alt) = d.checked_add(w) else {
+                continue;
+            };
+            if alt.is_infinity() {
+                continue;
+            }
+            if search.dist[v.index()].is_none_or(|current| alt < current) {
+                search.dist[v.index()] = Some(alt);
+                search.prev[v.index()] = Some(u);
+                queue.push(QueueEntry { dist: alt, cost: alt, id: v });
+            }
+        }
+    }
+    search
+}
+
+// walks the predecessors back from a vertex that was relaxed on the last bellman-ford pass.
+// that vertex is on or reachable from a negative cycle, so after V steps the walk is inside the cycle
+pub(super) fn negative_cycle(prev: &[Option<VertexId>], relaxed: VertexId, vert_count: usize) -> NegativeCycle<VertexId> {
+    let step = |v: VertexId| prev[v.index()].unwrap();
+    let mut start = relaxed;
+    for _ in 0..vert_count {
+        start = step(start);
+    }
+
+    let mut cycle = vec![start];
+    let mut u = step(start);
+    while u != start {
+        cycle.push(u);
+        u = step(u);
+    }
+    cycle.reverse();
+    NegativeCycle { cycle }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Graph;
+    use crate::graph::test_support::graph_from;
+
+    fn weighted_graph() -> Graph<i32> {
+        let mut g: Graph<i32> = Graph::new();
+        for id in ["a", "b", "c", "d", "e", "f"] {
+            g.add_vertex(&id.to_string(), &0);
+        }
+
+        let edges = [
+            ("a", "b", 7),
+            ("a", "c", 9),
+            ("a", "f", 14),
+            ("b", "c", 10),
+            ("b", "d", 15),
+            ("c", "d", 11),
+            ("c", "f", 2),
+            ("d", "e", 6),
+            ("f", "e", 9),
+        ];
+        for (src, dst, w) in edges {
+            g.add_weighted_edge(&src.to_string(), &dst.to_string(), w);
+        }
+        g
+    }
+
+    #[test]
+    fn shortest_path_tree_distances() {
+        let g = weighted_graph();
+        let tree = g.shortest_path_tree(&"a".to_string()).unwrap();
+
+        assert_eq!(tree.source(), "a");
+        assert_eq!(tree.distance(&"a".to_string()), Some(0));
+        assert_eq!(tree.distance(&"b".to_string()), Some(7));
+        assert_eq!(tree.distance(&"c".to_string()), Some(9));
+        assert_eq!(tree.distance(&"d".to_string()), Some(20));
+        assert_eq!(tree.distance(&"e".to_string()), Some(20));
+        assert_eq!(tree.distance(&"f".to_string()), Some(11));
+        assert_eq!(tree.distances().len(), 6);
+
+        assert_eq!(tree.predecessor(&"a".to_string()), None);
+        assert_eq!(tree.predecessor(&"f".to_string()), Some(&"c".to_string()));
+    }
+
+    #[test]
+    fn shortest_path_tree_paths() {
+        let g = weighted_graph();
+        let tree = g.shortest_path_tree(&"a".to_string()).unwrap();
+
+        assert_eq!(tree.path_to(&"a".to_string()).unwrap(), ["a"]);
+        assert_eq!(tree.path_to(&"e".to_string()).unwrap(), ["a", "c", "f", "e"]);
+        assert_eq!(tree.path_to(&"d".to_string()).unwrap(), ["a", "c", "d"]);
+        assert_eq!(g.shortest_path(&"a".to_string(), &"e".to_string()), ["a", "c", "f", "e"]);
+
+        // edges are directed so nothing is reachable from e
+        let tree = g.shortest_path_tree(&"e".to_string()).unwrap();
+        assert!(!tree.is_reachable(&"a".to_string()));
+        assert_eq!(tree.path_to(&"a".to_string()), None);
+        assert_eq!(tree.path_to(&"e".to_string()).unwrap(), ["e"]);
+
+        assert_eq!(g.shortest_path_tree(&"foo".to_string()), None);
+        assert_eq!(tree.path_to(&"foo".to_string()), None);
+    }
+
+    #[test]
+    fn shortest_path_tree_large() {
+        // a long chain plus shortcuts, big enough that an O(V^2) search would be noticeably slow
+        let mut g: Graph<i32> = Graph::new();
+        let size = 20000;
+        for i in 0..size {
+            g.add_vertex(&i.to_string(), &0);
+        }
+        for i in 0..size - 1 {
+            g.add_weighted_edge(&i.to_string(), &(i + 1).to_string(), 2);
+            if i + 10 < size {
+                g.add_weighted_edge(&i.to_string(), &(i + 10).to_string(), 15);
+            }
+        }
+
+        let tree = g.shortest_path_tree(&"0".to_string()).unwrap();
+        assert_eq!(tree.distances().len(), size as usize);
+        assert_eq!(tree.distance(&"10".to_string()), Some(15));
+        assert_eq!(tree.distance(&"11".to_string()), Some(17));
+        assert_eq!(tree.path_to(&"20".to_string()).unwrap(), ["0", "10", "20"]);
+    }
+
+    #[test]
+    fn shortest_path_tree_float_weights() {
+        let mut g: Graph<i32, f64> = Graph::new();
+        for id in ["a", "b", "c"] {
+            g.add_vertex(&id.to_string(), &0);
+        }
+        g.add_weighted_edge(&"a".to_string(), &"b".to_string(), 0.25);
+        g.add_weighted_edge(&"b".to_string(), &"c".to_string(), 0.5);
+        g.add_weighted_edge(&"a".to_string(), &"c".to_string(), 1.0);
+
+        let tree = g.shortest_path_tree(&"a".to_string()).unwrap();
+        assert_eq!(tree.distance(&"c".to_string()), Some(0.75));
+        assert_eq!(g.shortest_path(&"a".to_string(), &"c".to_string()), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn shortest_path_tree_does_not_overflow() {
+        let mut g: Graph<i32, u8> = Graph::new();
+        for id in ["a", "b", "c", "d"] {
+            g.add_vertex(&id.to_string(), &0);
+        }
+        g.add_weighted_edge(&"a".to_string(), &"b".to_string(), 200);
+        g.add_weighted_edge(&"b".to_string(), &"c".to_string(), 100);
+        g.add_weighted_edge(&"a".to_string(), &"d".to_string(), 254);
+
+        // 200 + 100 does not fit in a u8 so c can not be reached
+        let tree = g.shortest_path_tree(&"a".to_string()).unwrap();
+        assert_eq!(tree.distance(&"b".to_string()), Some(200));
+        assert_eq!(tree.distance(&"c".to_string()), None);
+        assert_eq!(tree.distance(&"d".to_string()), Some(254));
+        assert_eq!(g.shortest_path(&"a".to_string(), &"c".to_string()).len(), 0);
+    }
+
+    #[test]
+    fn infinite_weight_edges() {
+        let mut g: Graph<i32, u8> = Graph::new();
+        g.add_vertex(&"a".to_string(), &0);
+        g.add_vertex(&"b".to_string(), &0);
+        assert!(g.add_weighted_edge(&"a".to_string(), &"b".to_string(), u8::MAX));
+
+        // u8::MAX is the infinity of u8, so the edge is there but leads nowhere
+        assert_eq!(g.edge_weight(&"a".to_string(), &"b".to_string()), Some(u8::MAX));
+        let tree = g.shortest_path_tree(&"a".to_string()).unwrap();
+        assert_eq!(tree.distance(&"b".to_string()), None);
+        let tree = g.bellman_ford(&"a".to_string()).unwrap().unwrap();
+        assert_eq!(tree.distance(&"b".to_string()), None);
+    }
+
+    #[test]
+    fn shortest_path_tree_by_id() {
+        let g = weighted_graph();
+        let a = g.vertex_id(&"a".to_string()).unwrap();
+        let f = g.vertex_id(&"f".to_string()).unwrap();
+        let tree = g.shortest_path_tree_by_id(a).unwrap();
+        assert_eq!(tree.distance(&f), Some(11));
+        let path = tree.path_to(&f).unwrap();
+        let keys: Vec<_> = path.iter().map(|&v| g.vertex_key(v).unwrap()).collect();
+        assert_eq!(keys, ["a", "c", "f"]);
+
+        let path = g.find_path_by_id(a, f).unwrap();
+        assert_eq!(path.vertices, tree.path_to(&f).unwrap());
+        assert_eq!(path.cost, 11);
+        assert_eq!(g.shortest_path_by_id(f, a).len(), 0);
+    }
+
+    #[test]
+    fn bellman_ford_negative_weights() {
+        let g = graph_from(true, &[], &[("s", "a", 4), ("s", "b", 5), ("a", "c", 3), ("b", "a", -3), ("c", "d", 2), ("b", "d", 9)]);
+
+        let tree = g.bellman_ford(&"s".to_string()).unwrap().unwrap();
+        assert_eq!(tree.distance(&"a".to_string()), Some(2));
+        assert_eq!(tree.distance(&"c".to_string()), Some(5));
+        assert_eq!(tree.distance(&"d".to_string()), Some(7));
+        assert_eq!(tree.path_to(&"d".to_string()).unwrap(), ["s", "b", "a", "c", "d"]);
+
+        // dijkstra settles a before it sees the negative edge b->a
+        let tree = g.shortest_path_tree(&"s".to_string()).unwrap();
+        assert_eq!(tree.distance(&"a".to_string()), Some(4));
+
+        assert_eq!(g.bellman_ford(&"foo".to_string()), None);
+    }
+
+    #[test]
+    fn bellman_ford_negative_cycle() {
+        let g = graph_from(true, &[], &[("s", "a", 1), ("a", "b", 1), ("b", "c", -2), ("c", "a", -1), ("c", "d", 1)]);
+
+        let err = g.bellman_ford(&"s".to_string()).unwrap().unwrap_err();
+        let cycle = err.cycle();
+        assert_eq!(cycle.len(), 3);
+        for id in ["a", "b", "c"] {
+            assert!(cycle.contains(&id.to_string()));
+        }
+        // consecutive vertices, and the last back to the first, are joined by edges
+        for (i, id) in cycle.iter().enumerate() {
+            assert!(g.has_edge(id, &cycle[(i + 1) % cycle.len()]));
+        }
+        assert!(format!("{}", err).starts_with("negative cycle "));
+
+        // the cycle can't be reached from d
+        let tree = g.bellman_ford(&"d".to_string()).unwrap().unwrap();
+        assert_eq!(tree.distances().len(), 1);
+    }
+
+    #[test]
+    fn bellman_ford_negative_self_loop() {
+        let g = graph_from(true, &[], &[("a", "a", -1)]);
+        let err = g.bellman_ford(&"a".to_string()).unwrap().unwrap_err();
+        assert_eq!(err.cycle(), ["a"]);
+        assert_eq!(format!("{}", err), "negative cycle a->a");
+    }
+
+    #[test]
+    fn find_path_matches_tree() {
+        let g = weighted_graph();
+        let path = g.find_path(&"a".to_string(), &"e".to_string()).unwrap();
+        assert_eq!(path.vertices, ["a", "c", "f", "e"]);
+        assert_eq!(path.cost, 20);
+        assert_eq!(path.expanded, 6);
+
+        let path = g.find_path(&"a".to_string(), &"a".to_string()).unwrap();
+        assert_eq!(path.vertices, ["a"]);
+        assert_eq!(path.cost, 0);
+
+        assert_eq!(g.find_path(&"e".to_string(), &"a".to_string()), None);
+        assert_eq!(g.find_path(&"a".to_string(), &"foo".to_string()), None);
+    }
+
+    // size x size grid, payloads are the coordinates of each cell
+    fn grid(size: i32) -> Graph<(i32, i32)> {
+        let mut g: Graph<(i32, i32)> = Graph::new();
+        let id = |x: i32, y: i32| format!("{},{}", x, y);
+        for x in 0..size {
+            for y in 0..size {
+                g.add_vertex(&id(x, y), &(x, y));
+            }
+        }
+        for x in 0..size {
+            for y in 0..size {
+                for (nx, ny) in [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+                    g.add_edge(&id(x, y), &id(nx, ny));
+                }
+            }
+        }
+        g
+    }
+
+    #[test]
+    fn astar_on_grid() {
+        let g = grid(20);
+        let manhattan = |a: &(i32, i32), b: &(i32, i32)| (a.0 - b.0).abs() + (a.1 - b.1).abs();
+        let src = "0,0".to_string();
+        let dst = "19,19".to_string();
+
+        let astar = g.astar(&src, &dst, manhattan).unwrap();
+        let dijkstra = g.find_path(&src, &dst).unwrap();
+
+        assert_eq!(astar.cost, 38);
+        assert_eq!(astar.cost, dijkstra.cost);
+        assert_eq!(astar.vertices.len(), 39);
+        assert_eq!(astar.vertices.first(), Some(&src));
+        assert_eq!(astar.vertices.last(), Some(&dst));
+        assert!(astar.expanded < dijkstra.expanded);
+    }
+
+    #[test]
+    fn astar_unreachable() {
+        let mut g = grid(3);
+        g.add_vertex(&"island".to_string(), &(10, 10));
+        let manhattan = |a: &(i32, i32), b: &(i32, i32)| (a.0 - b.0).abs() + (a.1 - b.1).abs();
+
+        assert_eq!(g.astar(&"0,0".to_string(), &"island".to_string(), manhattan), None);
+        assert_eq!(g.astar(&"foo".to_string(), &"0,0".to_string(), manhattan), None);
+    }
+}
@@ -0,0 +1,434 @@
+use super::vertex::{Vertex, VertexTable};
+use super::{Graph, VertexId, Weight};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::Hash;
+use std::io::{self, Read, Write};
+
+// binary encoding of vertex keys, vertex values and edge weights for Graph::write_snapshot and Graph::read_snapshot.
+// implemented for the primitive types, String and (). numbers are little endian, usize and isize are written as
+// 64 bits and strings as a u64 byte length followed by utf-8
+pub trait Codec: Sized {
+    fn encode(&self, out: &mut Vec<u8>);
+
+    // reads a value from the front of bytes and moves bytes past it. None if bytes don't start with a valid value
+    fn decode(bytes: &mut &[u8]) -> Option<Self>;
+}
+
+// the first n bytes, moving bytes past them
+fn take<'a>(bytes: &mut &'a [u8], n: usize) -> Option<&'a [u8]> {
+    let (front, rest) = bytes.split_at_checked(n)?;
+    *bytes = rest;
+    Some(front)
+}
+
+macro_rules! impl_codec_number {
+    ($($t:ty as $repr:ty),*) => {
+        $(
+            impl Codec for $t {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&(*self as $repr).to_le_bytes());
+                }
+                fn decode(bytes: &mut &[u8]) -> Option<Self> {
+                    let front = take(bytes, std::mem::size_of::<$repr>())?;
+                    <$t>::try_from(<$repr>::from_le_bytes(front.try_into().unwrap())).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_codec_number!(u8 as u8, u16 as u16, u32 as u32, u64 as u64, u128 as u128, usize as u64);
+impl_codec_number!(i8 as i8, i16 as i16, i32 as i32, i64 as i64, i128 as i128, isize as i64);
+
+impl Codec for f32 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        Some(f32::from_le_bytes(take(bytes, 4)?.try_into().unwrap()))
+    }
+}
+
+impl Codec for f64 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        Some(f64::from_le_bytes(take(bytes, 8)?.try_into().unwrap()))
+    }
+}
+
+impl Codec for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        match take(bytes, 1)?[0] {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl Codec for char {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u32).encode(out);
+    }
+
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        char::from_u32(u32::decode(bytes)?)
+    }
+}
+
+impl Codec for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.len().encode(out);
+        out.extend_from_slice(self.as_bytes());
+    }
+
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        let len = usize::decode(bytes)?;
+        String::from_utf8(take(bytes, len)?.to_vec()).ok()
+    }
+}
+
+impl Codec for () {
+    fn encode(&self, _out: &mut Vec<u8>) {}
+
+    fn decode(_bytes: &mut &[u8]) -> Option<Self> {
+        Some(())
+    }
+}
+
+// error returned by Graph::read_snapshot
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    BadMagic,                // the input is not a graph snapshot
+    UnsupportedVersion(u32), // written by a newer or unknown version of the format
+    Truncated,               // the input ended before the header or body did
+    ChecksumMismatch,        // the body was changed after it was written
+    Corrupt(&'static str),   // the checksum matches but the contents don't describe a graph
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io(e) => write!(f, "{}", e),
+            SnapshotError::BadMagic => write!(f, "not a graph snapshot"),
+            SnapshotError::UnsupportedVersion(version) => write!(f, "unsupported snapshot version {}", version),
+            SnapshotError::Truncated => write!(f, "snapshot is truncated"),
+            SnapshotError::ChecksumMismatch => write!(f, "snapshot checksum does not match"),
+            SnapshotError::Corrupt(reason) => write!(f, "corrupt snapshot: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SnapshotError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SnapshotError {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::UnexpectedEof => SnapshotError::Truncated,
+            _ => SnapshotError::Io(e),
+        }
+    }
+}
+
+// snapshot layout, all integers little endian:
+//   header: magic "GRPHSNAP", version u32, flags u8 (bit 0 set when directed), body length u64, body checksum u64
+//   body:   slots u64 (one past the largest VertexId), vertex count u64, then per vertex its VertexId u32, key and value,
+//           edge count u64, then per edge src u32, dst u32, weight and payload. undirected edges are stored once.
+//           a () payload takes no space.
+// the gaps left by removed vertices are kept, but a snapshot may have at most MIN_GAPS of them or one per vertex
+// so a damaged or hostile header can't make read_snapshot allocate tables much larger than the body
+const MAGIC: &[u8; 8] = b"GRPHSNAP";
+const VERSION: u32 = 1;
+const HEADER_LEN: usize = 8 + 4 + 1 + 8 + 8;
+const DIRECTED: u8 = 1;
+const MIN_GAPS: usize = 1 << 16;
+
+// 64 bit FNV-1a, enough to catch damaged files
+fn checksum(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| (hash ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
+impl<T, W, K, E> Graph<T, W, K, E>
+where
+    T: Default + Clone + Codec,
+    W: Weight + Codec,
+    K: Hash + Eq + Clone + Codec,
+    E: Default + Clone + Codec,
+{
+    // writes the graph in a compact binary format that read_snapshot loads without looking up a key for every edge.
+    // VertexIds are kept, including the gaps left by removed vertices
+    pub fn write_snapshot<O: Write>(&self, mut writer: O) -> io::Result<()> {
+        let mut body = Vec::new();
+        self.verts.slots().encode(&mut body);
+        self.verts.len().encode(&mut body);
+        for (v, vertex) in self.verts.iter() {
+            (v.index() as u32).encode(&mut body);
+            vertex.id.encode(&mut body);
+            vertex.value.encode(&mut body);
+        }
+        self.edge_count.encode(&mut body);
+        for (src, adjacency_list) in self.adjacency_lists.iter().enumerate() {
+            for (&dst, adjacent) in adjacency_list.iter().filter(|(dst, _)| self.directed || src <= dst.index()) {
+                let edge = self.edge_view(VertexId::new(src), dst, adjacent);
+                (src as u32).encode(&mut body);
+                (dst.index() as u32).encode(&mut body);
+                edge.weight().encode(&mut body);
+                edge.payload().encode(&mut body);
+            }
+        }
+
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.extend_from_slice(MAGIC);
+        VERSION.encode(&mut header);
+        header.push(if self.directed { DIRECTED } else { 0 });
+        body.len().encode(&mut header);
+        checksum(&body).encode(&mut header);
+
+        writer.write_all(&header)?;
+        writer.write_all(&body)
+    }
+
+    // loads a graph written by write_snapshot. the magic number, version and checksum are checked before anything
+    // is decoded, and the contents are validated while the graph is rebuilt
+    pub fn read_snapshot<R: Read>(mut reader: R) -> Result<Self, SnapshotError> {
+        let mut header = [0; HEADER_LEN];
+        reader.read_exact(&mut header)?;
+        let mut header = &header[..];
+        if take(&mut header, MAGIC.len()) != Some(MAGIC) {
+            return Err(SnapshotError::BadMagic);
+        }
+        let version = u32::decode(&mut header).unwrap();
+        if version != VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+        let flags = u8::decode(&mut header).unwrap();
+        let len = u64::decode(&mut header).unwrap();
+        let expected = u64::decode(&mut header).unwrap();
+
+        let mut body = Vec::new();
+        reader.take(len).read_to_end(&mut body)?;
+        if body.len() as u64 != len {
+            return Err(SnapshotError::Truncated);
+        }
+        if checksum(&body) != expected {
+            return Err(SnapshotError::ChecksumMismatch);
+        }
+        if flags & !DIRECTED != 0 {
+            return Err(SnapshotError::Corrupt("unknown flags"));
+        }
+        Self::decode_body(&body, flags & DIRECTED != 0)
+    }
+
+    fn decode_body(mut body: &[u8], directed: bool) -> Result<Self, SnapshotError> {
+        let body = &mut body;
+        let slots = usize::decode(body)
+            .filter(|&slots| slots <= u32::MAX as usize)
+            .ok_or(SnapshotError::Corrupt("bad vertex slot count"))?;
+        // every vertex takes at least the 4 bytes of its VertexId
+        let vertex_count = usize::decode(body)
+            .filter(|&count| count <= slots && count <= body.len() / 4)
+            .ok_or(SnapshotError::Corrupt("bad vertex count"))?;
+        if slots - vertex_count > vertex_count.max(MIN_GAPS) {
+            return Err(SnapshotError::Corrupt("bad vertex slot count"));
+        }
+
+        let mut verts: Vec<Option<Vertex<K, T>>> = Vec::new();
+        verts.resize_with(slots, || None);
+        let mut last: Option<usize> = None;
+        for _ in 0..vertex_count {
+            let v = u32::decode(body).ok_or(SnapshotError::Corrupt("missing vertex"))? as usize;
+            if v >= slots || last.is_some_and(|last| v <= last) {
+                return Err(SnapshotError::Corrupt("vertex ids out of order"));
+            }
+            last = Some(v);
+            let id = K::decode(body).ok_or(SnapshotError::Corrupt("bad vertex key"))?;
+            let value = T::decode(body).ok_or(SnapshotError::Corrupt("bad vertex value"))?;
+            verts[v] = Some(Vertex { id, value });
+        }
+
+        let mut g = Graph {
+            verts: VertexTable::from_slots(verts).ok_or(SnapshotError::Corrupt("duplicate vertex key"))?,
+            adjacency_lists: vec![HashMap::new(); slots],
+            in_adjacency_lists: vec![HashSet::new(); slots],
+            edge_count: 0,
+            directed,
+        };
+        let edge_count = usize::decode(body).ok_or(SnapshotError::Corrupt("missing edge count"))?;
+        for _ in 0..edge_count {
+            let src = u32::decode(body).ok_or(SnapshotError::Corrupt("missing edge"))?;
+            let dst = u32::decode(body).ok_or(SnapshotError::Corrupt("missing edge"))?;
+            let w = W::decode(body).ok_or(SnapshotError::Corrupt("bad edge weight"))?;
+            let payload = E::decode(body).ok_or(SnapshotError::Corrupt("bad edge payload"))?;
+            if !g.add_edge_with_by_id(VertexId::new(src as usize), VertexId::new(dst as usize), w, payload) {
+                return Err(SnapshotError::Corrupt("edge to a missing vertex or repeated edge"));
+            }
+        }
+        if !body.is_empty() {
+            return Err(SnapshotError::Corrupt("trailing bytes"));
+        }
+        Ok(g)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{checksum, Codec, Graph, SnapshotError, HEADER_LEN};
+
+    #[derive(Debug, Clone, Default, PartialEq)]
+    struct Station {
+        name: String,
+        platforms: u8,
+    }
+
+    impl Codec for Station {
+        fn encode(&self, out: &mut Vec<u8>) {
+            self.name.encode(out);
+            self.platforms.encode(out);
+        }
+
+        fn decode(bytes: &mut &[u8]) -> Option<Self> {
+            Some(Station {
+                name: String::decode(bytes)?,
+                platforms: u8::decode(bytes)?,
+            })
+        }
+    }
+
+    fn snapshot<T, W, K, E>(g: &Graph<T, W, K, E>) -> Vec<u8>
+    where
+        T: Default + Clone + Codec,
+        W: super::Weight + Codec,
+        K: std::hash::Hash + Eq + Clone + Codec,
+        E: Default + Clone + Codec,
+    {
+        let mut bytes = Vec::new();
+        g.write_snapshot(&mut bytes).unwrap();
+        bytes
+    }
+
+    fn station(name: &str, platforms: u8) -> Station {
+        Station {
+            name: name.to_string(),
+            platforms,
+        }
+    }
+
+    #[test]
+    fn snapshot_round_trip() {
+        let mut g: Graph<Station> = Graph::new();
+        for (id, platforms) in [("kx", 12), ("stp", 15), ("eus", 18), ("pad", 14)] {
+            g.add_vertex(&id.to_string(), &station(&id.to_uppercase(), platforms));
+        }
+        g.add_weighted_edge(&"kx".to_string(), &"stp".to_string(), 2);
+        g.add_weighted_edge(&"stp".to_string(), &"eus".to_string(), 5);
+        g.add_weighted_edge(&"pad".to_string(), &"kx".to_string(), -7);
+        g.add_weighted_edge(&"eus".to_string(), &"pad".to_string(), 9);
+        g.remove_vertex(&"stp".to_string());
+
+        let loaded: Graph<Station> = Graph::read_snapshot(snapshot(&g).as_slice()).unwrap();
+        assert!(loaded.is_directed());
+        assert_eq!(loaded.vertex_count(), 3);
+        assert_eq!(loaded.edge_count(), 2);
+        assert_eq!(loaded.to_string(), g.to_string());
+        assert_eq!(loaded.get_value(&"pad".to_string()), Some(station("PAD", 14)));
+        assert_eq!(loaded.edge_weight(&"pad".to_string(), &"kx".to_string()), Some(-7));
+        for id in ["kx", "eus", "pad"] {
+            assert_eq!(loaded.vertex_id(&id.to_string()), g.vertex_id(&id.to_string()));
+        }
+        assert_eq!(loaded.in_degree(&"kx".to_string()), Some(1));
+
+        // new vertices don't reuse the handle of the removed one
+        let mut loaded = loaded;
+        loaded.add_vertex(&"stp".to_string(), &Station::default());
+        assert_eq!(loaded.vertex_id(&"stp".to_string()).unwrap().index(), 4);
+
+        let mut g: Graph<(), f64, u64> = Graph::new_undirected();
+        g.add_vertex(&7, &());
+        g.add_vertex(&3, &());
+        g.add_weighted_edge(&3, &7, 0.5);
+        g.add_weighted_edge(&7, &7, 1.5);
+        let loaded: Graph<(), f64, u64> = Graph::read_snapshot(snapshot(&g).as_slice()).unwrap();
+        assert!(!loaded.is_directed());
+        assert_eq!(loaded.edge_count(), 2);
+        assert_eq!(loaded.edge_weight(&7, &3), Some(0.5));
+        assert_eq!(loaded.edge_weight(&7, &7), Some(1.5));
+
+        // edge payloads are stored after the weight
+        let mut g: Graph<(), i32, String, String> = Graph::new_undirected();
+        g.add_vertex(&"a".to_string(), &());
+        g.add_vertex(&"b".to_string(), &());
+        g.add_edge_with(&"b".to_string(), &"a".to_string(), 3, "ring road".to_string());
+        let loaded: Graph<(), i32, String, String> = Graph::read_snapshot(snapshot(&g).as_slice()).unwrap();
+        let edge = loaded.get_edge(&"a".to_string(), &"b".to_string()).unwrap();
+        assert_eq!((edge.weight(), edge.payload().as_str()), (3, "ring road"));
+    }
+
+    #[test]
+    fn snapshot_errors() {
+        let mut g: Graph<i32> = Graph::new();
+        g.add_vertex(&"a".to_string(), &1);
+        g.add_vertex(&"b".to_string(), &2);
+        g.add_edge(&"a".to_string(), &"b".to_string());
+        let bytes = snapshot(&g);
+        let read = |bytes: &[u8]| Graph::<i32>::read_snapshot(bytes).unwrap_err();
+
+        for len in [0, 5, HEADER_LEN - 1, HEADER_LEN, bytes.len() - 1] {
+            assert!(matches!(read(&bytes[..len]), SnapshotError::Truncated), "{}", len);
+        }
+
+        let mut bad = bytes.clone();
+        bad[0] = b'X';
+        assert!(matches!(read(&bad), SnapshotError::BadMagic));
+        assert!(matches!(read(b"not a snapshot at all, not even close"), SnapshotError::BadMagic));
+
+        let mut bad = bytes.clone();
+        bad[8] = 2;
+        assert!(matches!(read(&bad), SnapshotError::UnsupportedVersion(2)));
+
+        let mut bad = bytes.clone();
+        *bad.last_mut().unwrap() ^= 1;
+        assert!(matches!(read(&bad), SnapshotError::ChecksumMismatch));
+
+        // a damaged body with a matching checksum is still caught. point the edge at a vertex that doesn't exist
+        let mut bad = bytes.clone();
+        let edge = bad.len() - 4 - 4;
+        bad[edge] = 9;
+        let sum = checksum(&bad[HEADER_LEN..]);
+        bad[HEADER_LEN - 8..HEADER_LEN].copy_from_slice(&sum.to_le_bytes());
+        let error = read(&bad);
+        assert_eq!(error.to_string(), "corrupt snapshot: edge to a missing vertex or repeated edge");
+
+        // counts in the body that the body is too short to hold are caught before anything is allocated
+        let empty = snapshot(&Graph::<i32>::new());
+        let with_counts = |slots: u64, vertex_count: u64| {
+            let mut bad = empty.clone();
+            bad[HEADER_LEN..HEADER_LEN + 8].copy_from_slice(&slots.to_le_bytes());
+            bad[HEADER_LEN + 8..HEADER_LEN + 16].copy_from_slice(&vertex_count.to_le_bytes());
+            let sum = checksum(&bad[HEADER_LEN..]);
+            bad[HEADER_LEN - 8..HEADER_LEN].copy_from_slice(&sum.to_le_bytes());
+            bad
+        };
+        assert_eq!(read(&with_counts(u32::MAX as u64, 0)).to_string(), "corrupt snapshot: bad vertex slot count");
+        assert_eq!(read(&with_counts(u32::MAX as u64, u32::MAX as u64)).to_string(), "corrupt snapshot: bad vertex count");
+        // a few gaps left by removed vertices are fine
+        let mut g = Graph::<i32>::read_snapshot(&with_counts(10, 0)[..]).unwrap();
+        assert_eq!(g.intern_vertex(&"a".to_string(), &1).index(), 10);
+    }
+}
@@ -0,0 +1,185 @@
+use super::{Graph, VertexId, Weight};
+use crate::disjoint_set::DisjointSet;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::hash::Hash;
+
+// minimum spanning forest found by Graph::kruskal or Graph::prim, one tree per connected component
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpanningForest<W, K = String> {
+    pub edges: Vec<(K, K, W)>, // chosen edges as (src, dst, weight), each in a direction it has in the graph
+    pub weight: W,             // sum of the weights of the chosen edges
+}
+
+// edge that could join the tree in prim's algorithm, ordered so the lightest pops first from a max heap
+struct Candidate<W> {
+    weight: W,
+    edge: (VertexId, VertexId),
+    next: VertexId, // the end of the edge outside the tree when it was pushed
+}
+
+impl<W: Weight> Ord for Candidate<W> {
+    fn cmp(&self, rhs: &Self) -> Ordering {
+        rhs.weight.weight_cmp(&self.weight).then_with(|| rhs.edge.cmp(&self.edge))
+    }
+}
+
+impl<W: Weight> PartialOrd for Candidate<W> {
+    fn partial_cmp(&self, rhs: &Self) -> Option<Ordering> {
+        Some(self.cmp(rhs))
+    }
+}
+
+impl<W: Weight> PartialEq for Candidate<W> {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.cmp(rhs) == Ordering::Equal
+    }
+}
+
+impl<W: Weight> Eq for Candidate<W> {}
+
+impl<T, W, K, E> Graph<T, W, K, E>
+where
+    T: Default + Clone,
+    W: Weight,
+    K: Hash + Eq + Clone,
+    E: Default + Clone,
+{
+    // kruskal's algorithm. minimum spanning forest of the graph with every edge treated as undirected,
+    // so in a directed graph a->b and b->a are two candidates for the same link. self loops are never chosen.
+    // edges of equal weight are taken in VertexId order, and the edges are returned in the order they were chosen
+    pub fn kruskal(&self) -> SpanningForest<W, K> {
+        let mut edges: Vec<(VertexId, VertexId, W)> = self.arcs().filter(|&(u, v, _)| u != v).collect();
+        edges.sort_by(|a, b| a.2.weight_cmp(&b.2).then_with(|| (a.0, a.1).cmp(&(b.0, b.1))));
+
+        let mut sets = DisjointSet::new(self.verts.slots());
+        let chosen: Vec<_> = edges.into_iter().filter(|&(u, v, _)| sets.union(u.index(), v.index())).collect();
+        self.spanning_forest(chosen)
+    }
+
+    // prim's algorithm with a binary heap. gives a forest of the same weight as kruskal, growing one tree at a time
+    // from each vertex not yet spanned, in the order the vertices were added.
+    // every edge is treated as undirected and the edges are returned in the order they joined the forest
+    pub fn prim(&self) -> SpanningForest<W, K> {
+        let mut spanned = vec![false; self.verts.slots()];
+        let mut heap: BinaryHeap<Candidate<W>> = BinaryHeap::new();
+        let mut chosen = Vec::new();
+
+        for root in self.verts.ids() {
+            if spanned[root.index()] {
+                continue;
+            }
+            spanned[root.index()] = true;
+            self.push_candidates(root, &spanned, &mut heap);
+
+            while let Some(Candidate { weight, edge, next }) = heap.pop() {
+                if spanned[next.index()] {
+                    continue;
+                }
+                spanned[next.index()] = true;
+                chosen.push((edge.0, edge.1, weight));
+                self.push_candidates(next, &spanned, &mut heap);
+            }
+        }
+        self.spanning_forest(chosen)
+    }
+
+    // pushes every edge into or out of u whose other end is not spanned yet
+    fn push_candidates(&self, u: VertexId, spanned: &[bool], heap: &mut BinaryHeap<Candidate<W>>) {
+        for (&v, edge) in &self.adjacency_lists[u.index()] {
+            if !spanned[v.index()] {
+                heap.push(Candidate {
+                    weight: edge.weight,
+                    edge: (u, v),
+                    next: v,
+                });
+            }
+        }
+        if self.directed {
+            for &v in &self.in_adjacency_lists[u.index()] {
+                if !spanned[v.index()] {
+                    let weight = self.adjacency_lists[v.index()][&u].weight;
+                    heap.push(Candidate { weight, edge: (v, u), next: v });
+                }
+            }
+        }
+    }
+
+    fn spanning_forest(&self, chosen: Vec<(VertexId, VertexId, W)>) -> SpanningForest<W, K> {
+        let weight = chosen.iter().fold(W::zero(), |total, &(_, _, w)| total.saturating_add(w));
+        SpanningForest {
+            edges: chosen.into_iter().map(|(u, v, w)| (self.verts.key(u).clone(), self.verts.key(v).clone(), w)).collect(),
+            weight,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Graph;
+    use crate::graph::test_support::graph_from;
+
+    fn sorted_edges(edges: &[(String, String, i32)]) -> Vec<(String, String, i32)> {
+        let mut edges: Vec<_> = edges
+            .iter()
+            .map(|(u, v, w)| if u < v { (u.clone(), v.clone(), *w) } else { (v.clone(), u.clone(), *w) })
+            .collect();
+        edges.sort();
+        edges
+    }
+
+    #[test]
+    fn minimum_spanning_tree() {
+        // the example from cormen et al. with a unique minimum spanning tree of weight 37
+        let g = graph_from(
+            false,
+            &[],
+            &[
+                ("a", "b", 4),
+                ("a", "h", 8),
+                ("b", "c", 8),
+                ("b", "h", 11),
+                ("c", "d", 7),
+                ("c", "f", 4),
+                ("c", "i", 2),
+                ("d", "e", 9),
+                ("d", "f", 14),
+                ("e", "f", 10),
+                ("f", "g", 2),
+                ("g", "h", 1),
+                ("g", "i", 6),
+                ("h", "i", 7),
+            ],
+        );
+
+        let kruskal = g.kruskal();
+        let prim = g.prim();
+        assert_eq!(kruskal.weight, 37);
+        assert_eq!(prim.weight, 37);
+        assert_eq!(kruskal.edges.len(), 8);
+        assert_eq!(kruskal.edges[0], ("h".to_string(), "g".to_string(), 1));
+        assert_eq!(prim.edges[0], ("a".to_string(), "b".to_string(), 4));
+        assert_eq!(sorted_edges(&kruskal.edges), sorted_edges(&prim.edges));
+    }
+
+    #[test]
+    fn spanning_forest_of_directed_graph() {
+        // two components, edge directions and self loops are ignored, the lighter of a->b and b->a wins
+        let mut g = graph_from(true, &[], &[("a", "b", 5), ("b", "a", 2), ("c", "b", 3), ("a", "c", 4), ("x", "y", 7), ("y", "y", -1)]);
+        g.add_vertex(&"z".to_string(), &0);
+
+        for forest in [g.kruskal(), g.prim()] {
+            assert_eq!(forest.weight, 12);
+            assert_eq!(
+                sorted_edges(&forest.edges),
+                [
+                    ("a".to_string(), "b".to_string(), 2),
+                    ("b".to_string(), "c".to_string(), 3),
+                    ("x".to_string(), "y".to_string(), 7)
+                ]
+            );
+        }
+        assert!(g.prim().edges.contains(&("c".to_string(), "b".to_string(), 3)));
+        assert_eq!(Graph::<i32>::new().kruskal().weight, 0);
+    }
+}
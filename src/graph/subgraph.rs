@@ -0,0 +1,239 @@
+use super::{Edge, Graph, GraphView, VertexId, Weight};
+use std::collections::HashSet;
+use std::hash::Hash;
+
+// a Graph seen through a vertex and an edge filter, see Graph::filtered. nothing is copied, vertices and edges
+// that don't pass the filters are skipped as the view is walked
+pub struct Filtered<'a, T, W, K, E, V, F> {
+    graph: &'a Graph<T, W, K, E>,
+    vertex: V,
+    edge: F,
+}
+
+impl<T, W, K, E> Graph<T, W, K, E>
+where
+    T: Default + Clone,
+    W: Weight,
+    K: Hash + Eq + Clone,
+    E: Default + Clone,
+{
+    // a copy of the graph with only the given vertices and the edges between them. keys that are not in the
+    // graph are ignored. vertices keep their values and the order they were added in
+    pub fn induced_subgraph(&self, verts: &HashSet<K>) -> Graph<T, W, K, E> {
+        self.subgraph_where(|v| verts.contains(self.verts.key(v)), |_, _, _| true)
+    }
+
+    // a copy of the graph with every vertex and only the edges src->dst for which keep is true.
+    // keep is called once for an undirected edge, with src the vertex that was added first
+    pub fn edge_subgraph<F>(&self, keep: F) -> Graph<T, W, K, E>
+    where
+        F: Fn(&K, &K, Edge<'_, W, E>) -> bool,
+    {
+        self.subgraph_where(|_| true, |src, dst, edge| keep(self.verts.key(src), self.verts.key(dst), edge))
+    }
+
+    // a view of the graph with only the vertices for which vertex is true and the edges between them for which
+    // edge is true. the GraphView queries such as shortest_path and search run on it without copying or changing
+    // the graph. vertex_count and edge_count walk the graph on every call.
+    // for an undirected graph edge should give the same answer for src->dst and dst->src
+    pub fn filtered<V, F>(&self, vertex: V, edge: F) -> Filtered<'_, T, W, K, E, V, F>
+    where
+        V: Fn(&K, &T) -> bool,
+        F: Fn(&K, &K, Edge<'_, W, E>) -> bool,
+    {
+        Filtered { graph: self, vertex, edge }
+    }
+
+    fn subgraph_where<V, F>(&self, keep_vertex: V, keep_edge: F) -> Graph<T, W, K, E>
+    where
+        V: Fn(VertexId) -> bool,
+        F: Fn(VertexId, VertexId, Edge<'_, W, E>) -> bool,
+    {
+        let mut g = Graph {
+            directed: self.directed,
+            ..Graph::new()
+        };
+        // handle of every kept vertex in the subgraph
+        let mut ids: Vec<Option<VertexId>> = vec![None; self.verts.slots()];
+        for (v, vertex) in self.verts.iter().filter(|&(v, _)| keep_vertex(v)) {
+            ids[v.index()] = Some(g.intern_vertex(&vertex.id, &vertex.value));
+        }
+
+        for (src, adjacency_list) in self.adjacency_lists.iter().enumerate() {
+            let src = VertexId::new(src);
+            let Some(u) = ids[src.index()] else {
+                continue;
+            };
+            for (&dst, adjacent) in adjacency_list.iter().filter(|(dst, _)| self.directed || src <= **dst) {
+                let edge = self.edge_view(src, dst, adjacent);
+                if let Some(v) = ids[dst.index()] {
+                    if keep_edge(src, dst, edge) {
+                        g.add_edge_with_by_id(u, v, edge.weight(), edge.payload().clone());
+                    }
+                }
+            }
+        }
+        g
+    }
+}
+
+impl<T, W, K, E, V, F> Filtered<'_, T, W, K, E, V, F>
+where
+    K: Hash + Eq + Clone,
+    V: Fn(&K, &T) -> bool,
+    F: Fn(&K, &K, Edge<'_, W, E>) -> bool,
+{
+    fn keeps_vertex(&self, v: VertexId) -> bool {
+        self.graph.verts.get(v).is_some_and(|vertex| (self.vertex)(&vertex.id, &vertex.value))
+    }
+
+    fn keeps_edge(&self, src: VertexId, dst: VertexId, edge: Edge<'_, W, E>) -> bool {
+        self.keeps_vertex(dst) && (self.edge)(self.graph.verts.key(src), self.graph.verts.key(dst), edge)
+    }
+}
+
+impl<T, W, K, E, V, F> GraphView for Filtered<'_, T, W, K, E, V, F>
+where
+    T: Default + Clone,
+    W: Weight,
+    K: Hash + Eq + Clone,
+    E: Default + Clone,
+    V: Fn(&K, &T) -> bool,
+    F: Fn(&K, &K, Edge<'_, W, E>) -> bool,
+{
+    type Key = K;
+    type Value = T;
+    type Weight = W;
+
+    fn is_directed(&self) -> bool {
+        self.graph.directed
+    }
+
+    fn vertex_count(&self) -> usize {
+        self.vertex_ids().count()
+    }
+
+    fn edge_count(&self) -> usize {
+        let directed = self.graph.directed;
+        self.vertex_ids().map(|v| self.out_edges(v).filter(|&(u, _)| directed || v <= u).count()).sum()
+    }
+
+    fn id_bound(&self) -> usize {
+        self.graph.verts.slots()
+    }
+
+    fn vertex_id(&self, id: &K) -> Option<VertexId> {
+        self.graph.verts.id(id).filter(|&v| self.keeps_vertex(v))
+    }
+
+    fn vertex_key(&self, v: VertexId) -> Option<&K> {
+        self.keeps_vertex(v).then(|| self.graph.verts.key(v))
+    }
+
+    fn value(&self, v: VertexId) -> Option<&T> {
+        self.keeps_vertex(v).then(|| &self.graph.verts.get(v).unwrap().value)
+    }
+
+    fn vertex_ids(&self) -> impl Iterator<Item = VertexId> + '_ {
+        self.graph.verts.ids().filter(|&v| self.keeps_vertex(v))
+    }
+
+    fn out_edges(&self, v: VertexId) -> impl Iterator<Item = (VertexId, W)> + '_ {
+        let adjacency_list = self.keeps_vertex(v).then(|| &self.graph.adjacency_lists[v.index()]);
+        adjacency_list
+            .into_iter()
+            .flatten()
+            .filter(move |&(&u, adjacent)| self.keeps_edge(v, u, self.graph.edge_view(v, u, adjacent)))
+            .map(|(&u, edge)| (u, edge.weight))
+    }
+
+    fn edge_weight_by_id(&self, src: VertexId, dst: VertexId) -> Option<W> {
+        let edge = self.graph.get_edge_by_id(src, dst)?;
+        (self.keeps_vertex(src) && self.keeps_edge(src, dst, edge)).then_some(edge.weight())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Graph, GraphView};
+    use std::collections::HashSet;
+
+    // a ring a-b-c-d-a of weight 1 edges with a weight 10 shortcut a-c
+    fn ring(directed: bool) -> Graph<i32, i32, String, bool> {
+        let mut g = if directed { Graph::new() } else { Graph::new_undirected() };
+        for (i, id) in ["a", "b", "c", "d"].into_iter().enumerate() {
+            g.add_vertex(&id.to_string(), &(i as i32));
+        }
+        for (src, dst) in [("a", "b"), ("b", "c"), ("c", "d"), ("d", "a")] {
+            g.add_edge_with(&src.to_string(), &dst.to_string(), 1, false);
+        }
+        g.add_edge_with(&"a".to_string(), &"c".to_string(), 10, true);
+        g
+    }
+
+    fn set(ids: &[&str]) -> HashSet<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn induced_subgraph() {
+        let g = ring(true);
+        let sub = g.induced_subgraph(&set(&["a", "c", "d", "x"]));
+        assert!(sub.is_directed());
+        assert_eq!(sub.vertex_count(), 3);
+        assert_eq!(sub.display().with_weights().to_string(), "a->c:10;c->d:1;d->a:1;");
+        assert_eq!(sub.get_value(&"d".to_string()), Some(3));
+        assert!(sub.get_edge(&"a".to_string(), &"c".to_string()).unwrap().payload());
+
+        let g = ring(false);
+        let sub = g.induced_subgraph(&set(&["a", "b", "c"]));
+        assert_eq!(sub.edge_count(), 3);
+        assert!(sub.has_edge(&"c".to_string(), &"a".to_string()));
+        assert_eq!(g.induced_subgraph(&HashSet::new()).vertex_count(), 0);
+    }
+
+    #[test]
+    fn edge_subgraph() {
+        let g = ring(false);
+        let sub = g.edge_subgraph(|_, _, edge| !edge.payload());
+        assert_eq!(sub.vertex_count(), 4);
+        assert_eq!(sub.edge_count(), 4);
+        assert!(!sub.has_edge(&"c".to_string(), &"a".to_string()));
+
+        let sub = g.edge_subgraph(|src, _, edge| src == "a" && edge.weight() < 10);
+        assert_eq!(sub.to_string(), "a-b,a-d;c;");
+    }
+
+    #[test]
+    fn filtered_view() {
+        let (a, b, c, d) = ("a".to_string(), "b".to_string(), "c".to_string(), "d".to_string());
+        let g = ring(true);
+        let failed = set(&["b"]);
+        let view = g.filtered(|id, _| !failed.contains(id), |_, _, _| true);
+
+        // with b down the path goes over the shortcut
+        assert_eq!(g.shortest_path(&a, &c), [a.clone(), b.clone(), c.clone()]);
+        let path = view.find_path(&a, &c).unwrap();
+        assert_eq!(path.vertices, [a.clone(), c.clone()]);
+        assert_eq!(path.cost, 10);
+        assert_eq!(view.shortest_path(&a, &b), Vec::<String>::new());
+        assert_eq!(view.search(&d, &c), Some(c.clone()));
+        assert_eq!(view.search(&a, &b), None);
+        assert_eq!((view.vertex_count(), view.edge_count()), (3, 3));
+        assert_eq!(view.vertex_id(&b), None);
+        assert_eq!(view.edge_weight_by_id(g.vertex_id(&a).unwrap(), g.vertex_id(&b).unwrap()), None);
+
+        // without shortcuts and with b down, c can't be reached from a at all
+        let view = g.filtered(|id, _| !failed.contains(id), |_, _, edge| !edge.payload());
+        assert!(view.find_path(&a, &c).is_none());
+        assert_eq!(view.shortest_path(&c, &a), [c.clone(), d.clone(), a.clone()]);
+        assert_eq!(view.edge_count(), 2);
+
+        // vertices can be filtered on their values, and undirected edges are counted once
+        let g = ring(false);
+        let view = g.filtered(|_, &value| value != 3, |_, _, _| true);
+        assert_eq!((view.vertex_count(), view.edge_count()), (3, 3));
+        assert_eq!(view.shortest_path(&c, &a), [c.clone(), b.clone(), a.clone()]);
+        assert_eq!(g.edge_count(), 5);
+    }
+}
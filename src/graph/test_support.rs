@@ -0,0 +1,15 @@
+use super::Graph;
+
+// an i32 graph for tests. verts are added first and then the ends of edges as they come, and every vertex gets the
+// order it was added in as its value
+pub(super) fn graph_from(directed: bool, verts: &[&str], edges: &[(&str, &str, i32)]) -> Graph<i32> {
+    let mut g: Graph<i32> = if directed { Graph::new() } else { Graph::new_undirected() };
+    for id in verts.iter().chain(edges.iter().flat_map(|(src, dst, _)| [src, dst])) {
+        let value = g.vertex_count() as i32;
+        g.add_vertex(&id.to_string(), &value);
+    }
+    for (src, dst, w) in edges {
+        g.add_weighted_edge(&src.to_string(), &dst.to_string(), *w);
+    }
+    g
+}
@@ -0,0 +1,241 @@
+use super::{Graph, VertexId, Weight};
+use std::fmt::{self, Display};
+use std::hash::Hash;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+// error returned by Graph::read_edge_list and Graph::read_adjacency_list
+#[derive(Debug)]
+pub enum ReadError {
+    Io(io::Error),
+    Parse { line: usize, message: String }, // line is 1 based
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io(e) => write!(f, "{}", e),
+            ReadError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for ReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadError::Io(e) => Some(e),
+            ReadError::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(e: io::Error) -> Self {
+        ReadError::Io(e)
+    }
+}
+
+impl<T, W, K, E> Graph<T, W, K, E>
+where
+    T: Default + Clone,
+    W: Weight,
+    K: Hash + Eq + Clone,
+    E: Default + Clone,
+{
+    // adds the edges of a whitespace separated edge list, one "src dst [weight]" edge per line as in the SNAP datasets.
+    // edges without a weight get W::one(), and vertices are added with T::default() the first time they are named.
+    // blank lines and lines starting with # are skipped. the input is read a line at a time, and edges read before
+    // an error stay in the graph
+    pub fn read_edge_list<R: BufRead>(&mut self, reader: R) -> Result<(), ReadError>
+    where
+        W: FromStr,
+        K: FromStr,
+    {
+        for_each_line(reader, |fields| {
+            let (src, dst, weight) = match fields {
+                [src, dst] => (src, dst, None),
+                [src, dst, weight] => (src, dst, Some(weight)),
+                _ => return Err(format!("expected 2 or 3 columns, found {}", fields.len())),
+            };
+            let src = self.intern_parsed(src)?;
+            let dst = self.intern_parsed(dst)?;
+            self.add_weighted_edge_by_id(src, dst, parse_weight(weight.copied())?);
+            Ok(())
+        })
+    }
+
+    // adds the vertices and edges of an adjacency list, one "src dst1 dst2 ..." line per vertex. a neighbor can carry
+    // a weight after its last colon, as in dst:weight, and gets W::one() otherwise. a line with only src adds an
+    // isolated vertex. blank lines and lines starting with # are skipped
+    pub fn read_adjacency_list<R: BufRead>(&mut self, reader: R) -> Result<(), ReadError>
+    where
+        W: FromStr,
+        K: FromStr,
+    {
+        for_each_line(reader, |fields| {
+            let src = self.intern_parsed(fields[0])?;
+            for field in &fields[1..] {
+                let (dst, weight) = match field.rsplit_once(':') {
+                    Some((dst, weight)) => (dst, Some(weight)),
+                    None => (*field, None),
+                };
+                let dst = self.intern_parsed(dst)?;
+                self.add_weighted_edge_by_id(src, dst, parse_weight(weight)?);
+            }
+            Ok(())
+        })
+    }
+
+    // writes every edge as a "src dst" line, followed by the weight if weights is true, ordered by source and
+    // destination. undirected edges are written once. isolated vertices are not written, see write_adjacency_list
+    pub fn write_edge_list<O: Write>(&self, mut writer: O, weights: bool) -> io::Result<()>
+    where
+        W: Display,
+        K: Display,
+    {
+        for (src, dst, w) in self.sorted_edges() {
+            write!(writer, "{} {}", self.verts.key(src), self.verts.key(dst))?;
+            if weights {
+                write!(writer, " {}", w)?;
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+
+    // writes a line for every vertex in the order they were added, listing the destinations of its out edges as
+    // dst or dst:weight. undirected edges are listed once, under the vertex that was added first
+    pub fn write_adjacency_list<O: Write>(&self, mut writer: O, weights: bool) -> io::Result<()>
+    where
+        W: Display,
+        K: Display,
+    {
+        let edges = self.sorted_edges();
+        let mut edges = edges.iter().peekable();
+        for (v, vertex) in self.verts.iter() {
+            write!(writer, "{}", vertex.id)?;
+            while let Some((_, dst, w)) = edges.next_if(|(src, _, _)| *src == v) {
+                write!(writer, " {}", self.verts.key(*dst))?;
+                if weights {
+                    write!(writer, ":{}", w)?;
+                }
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+
+    fn intern_parsed(&mut self, field: &str) -> Result<VertexId, String>
+    where
+        K: FromStr,
+    {
+        let id: K = field.parse().map_err(|_| format!("invalid vertex {:?}", field))?;
+        Ok(self.intern_vertex(&id, &T::default()))
+    }
+}
+
+fn parse_weight<W: Weight + FromStr>(field: Option<&str>) -> Result<W, String> {
+    match field {
+        Some(field) => field.parse().map_err(|_| format!("invalid weight {:?}", field)),
+        None => Ok(W::one()),
+    }
+}
+
+// calls f with the whitespace separated fields of every line that isn't blank or a comment, adding the line number
+// to the errors it returns
+fn for_each_line<R, F>(reader: R, mut f: F) -> Result<(), ReadError>
+where
+    R: BufRead,
+    F: FnMut(&[&str]) -> Result<(), String>,
+{
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.is_empty() || fields[0].starts_with('#') {
+            continue;
+        }
+        f(&fields).map_err(|message| ReadError::Parse { line: i + 1, message })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Graph, ReadError};
+
+    #[test]
+    fn edge_list() {
+        let input = "# Directed graph: example.txt\n# FromNodeId\tToNodeId\n0\t1\n0 2   4\n\n  # indented comment\n2 0\n1 1 3\n";
+        let mut g: Graph<i32, i32, u32> = Graph::new();
+        g.read_edge_list(input.as_bytes()).unwrap();
+        assert_eq!(g.vertex_count(), 3);
+        assert_eq!(g.edge_count(), 4);
+        assert_eq!(g.edge_weight(&0, &2), Some(4));
+        assert_eq!(g.edge_weight(&0, &1), Some(1));
+        assert_eq!(g.get_value(&2), Some(0));
+
+        let mut out = Vec::new();
+        g.write_edge_list(&mut out, true).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "0 1 1\n0 2 4\n1 1 3\n2 0 1\n");
+        let mut out = Vec::new();
+        g.write_edge_list(&mut out, false).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "0 1\n0 2\n1 1\n2 0\n");
+
+        // undirected edges are written once and read back the same
+        let mut g: Graph<()> = Graph::new_undirected();
+        g.read_edge_list("b a 2\nc b\n".as_bytes()).unwrap();
+        let mut out = Vec::new();
+        g.write_edge_list(&mut out, true).unwrap();
+        assert_eq!(String::from_utf8(out.clone()).unwrap(), "b a 2\nb c 1\n");
+        let mut again: Graph<()> = Graph::new_undirected();
+        again.read_edge_list(out.as_slice()).unwrap();
+        assert_eq!(again.edge_count(), 2);
+        assert_eq!(again.edge_weight(&"a".to_string(), &"b".to_string()), Some(2));
+    }
+
+    #[test]
+    fn adjacency_list() {
+        let input = "# adjacency\na b:2 c\nb c:-1\nd\nc a\n";
+        let mut g: Graph<String> = Graph::new();
+        g.read_adjacency_list(input.as_bytes()).unwrap();
+        assert_eq!(g.vertex_count(), 4);
+        assert_eq!(g.edge_count(), 4);
+        assert_eq!(g.edge_weight(&"b".to_string(), &"c".to_string()), Some(-1));
+        assert_eq!(g.out_degree(&"d".to_string()), Some(0));
+
+        let mut out = Vec::new();
+        g.write_adjacency_list(&mut out, true).unwrap();
+        let written = String::from_utf8(out).unwrap();
+        assert_eq!(written, "a b:2 c:1\nb c:-1\nc a:1\nd\n");
+
+        let mut again: Graph<String> = Graph::new();
+        again.read_adjacency_list(written.as_bytes()).unwrap();
+        let mut out = Vec::new();
+        again.write_adjacency_list(&mut out, false).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "a b c\nb c\nc a\nd\n");
+    }
+
+    #[test]
+    fn read_errors() {
+        let parse_error = |result: Result<(), ReadError>| match result {
+            Err(ReadError::Parse { line, message }) => (line, message),
+            other => panic!("expected a parse error, got {:?}", other),
+        };
+
+        let mut g: Graph<i32, i32, u32> = Graph::new();
+        let (line, message) = parse_error(g.read_edge_list("# c\n1 2\n\n1 x\n".as_bytes()));
+        assert_eq!((line, message.as_str()), (4, "invalid vertex \"x\""));
+        // edges before the error are kept
+        assert!(g.has_edge(&1, &2));
+
+        let (line, message) = parse_error(g.read_edge_list("1 2 3 4\n".as_bytes()));
+        assert_eq!((line, message.as_str()), (1, "expected 2 or 3 columns, found 4"));
+        let (line, message) = parse_error(g.read_adjacency_list("1 2\n2 3:1.5\n".as_bytes()));
+        assert_eq!((line, message.as_str()), (2, "invalid weight \"1.5\""));
+
+        let error = g.read_edge_list("1\n".as_bytes()).unwrap_err();
+        assert_eq!(error.to_string(), "line 1: expected 2 or 3 columns, found 1");
+        let error = g.read_edge_list(&[0xff, b'\n'][..]).unwrap_err();
+        assert!(matches!(error, ReadError::Io(_)));
+    }
+}
@@ -0,0 +1,221 @@
+use super::{Control, Graph, GraphView, VertexId, Visitor, Weight};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::hash::Hash;
+
+// error returned by the topological sorts when the graph has a directed cycle
+#[derive(Debug, Clone, PartialEq)]
+pub struct CycleError<K = String> {
+    cycle: Vec<K>, // vertices of the cycle in edge order, the last vertex has an edge back to the first
+}
+
+impl<K> CycleError<K> {
+    pub fn cycle(&self) -> &[K] {
+        &self.cycle
+    }
+}
+
+impl<K> fmt::Display for CycleError<K>
+where
+    K: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cycle ")?;
+        for id in &self.cycle {
+            write!(f, "{}->", id)?;
+        }
+        match self.cycle.first() {
+            Some(first) => write!(f, "{}", first),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<K> std::error::Error for CycleError<K> where K: fmt::Debug + fmt::Display {}
+
+impl<T, W, K, E> Graph<T, W, K, E>
+where
+    T: Default + Clone,
+    W: Weight,
+    K: Hash + Eq + Clone,
+    E: Default + Clone,
+{
+    // kahn's algorithm. orders the vertices so that every edge goes from an earlier vertex to a later one.
+    // when several vertices could come next the one added to the graph first is picked, so the order is deterministic.
+    // returns a CycleError naming one cycle if there is no such order
+    pub fn topological_sort(&self) -> Result<Vec<K>, CycleError<K>> {
+        let mut in_degree: Vec<usize> = vec![0; self.verts.slots()];
+        for (_, dst, _) in self.arcs() {
+            in_degree[dst.index()] += 1;
+        }
+
+        let mut ready: BinaryHeap<Reverse<VertexId>> = self.verts.ids().filter(|v| in_degree[v.index()] == 0).map(Reverse).collect();
+        let mut order: Vec<K> = Vec::with_capacity(self.verts.len());
+
+        while let Some(Reverse(u)) = ready.pop() {
+            order.push(self.verts.key(u).clone());
+            for v in self.adjacent_ids(u) {
+                let d = &mut in_degree[v.index()];
+                *d -= 1;
+                if *d == 0 {
+                    ready.push(Reverse(v));
+                }
+            }
+        }
+
+        if order.len() < self.verts.len() {
+            return Err(self.find_cycle().unwrap());
+        }
+        Ok(order)
+    }
+
+    // topological sort from the reverse postorder of a depth first search.
+    // returns a CycleError with the cycle closed by the first back edge found
+    pub fn topological_sort_dfs(&self) -> Result<Vec<K>, CycleError<K>> {
+        let mut order = self.dfs_postorder().map_err(|cycle| self.cycle_error(cycle))?;
+        order.reverse();
+        Ok(order.into_iter().map(|v| self.verts.key(v).clone()).collect())
+    }
+
+    // true if the graph has no directed cycle
+    pub fn is_acyclic(&self) -> bool {
+        self.dfs_postorder().is_ok()
+    }
+
+    fn find_cycle(&self) -> Option<CycleError<K>> {
+        self.dfs_postorder().err().map(|cycle| self.cycle_error(cycle))
+    }
+
+    fn cycle_error(&self, cycle: Vec<VertexId>) -> CycleError<K> {
+        CycleError {
+            cycle: cycle.into_iter().map(|v| self.verts.key(v).clone()).collect(),
+        }
+    }
+
+    // depth first search over every vertex, returning the vertices in the order they are finished,
+    // or the cycle closed by the first back edge. an undirected edge is a cycle between its two vertices
+    fn dfs_postorder(&self) -> Result<Vec<VertexId>, Vec<VertexId>> {
+        if !self.directed {
+            return match self.arcs().next() {
+                Some((u, v, _)) if u == v => Err(vec![u]),
+                Some((u, v, _)) => Err(vec![u, v]),
+                None => Ok(self.verts.ids().collect()),
+            };
+        }
+
+        let mut postorder = Postorder::default();
+        self.dfs_all(&mut postorder);
+        match postorder.cycle {
+            Some(cycle) => Err(cycle),
+            None => Ok(postorder.finished),
+        }
+    }
+}
+
+// records the order vertices are finished in, stopping at the first back edge
+#[derive(Default)]
+struct Postorder {
+    path: Vec<VertexId>, // the current dfs path
+    finished: Vec<VertexId>,
+    cycle: Option<Vec<VertexId>>,
+}
+
+impl<W> Visitor<W> for Postorder {
+    fn discover(&mut self, v: VertexId) -> Control {
+        self.path.push(v);
+        Control::Continue
+    }
+
+    fn finish(&mut self, v: VertexId) -> Control {
+        self.path.pop();
+        self.finished.push(v);
+        Control::Continue
+    }
+
+    // back edge u->v, the path from v to u is a cycle
+    fn back_edge(&mut self, _src: VertexId, dst: VertexId, _weight: W) -> Control {
+        let start = self.path.iter().position(|&v| v == dst).unwrap();
+        self.cycle = Some(self.path[start..].to_vec());
+        Control::Stop
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Graph;
+    use crate::graph::test_support::graph_from;
+
+    // every edge goes forwards in order
+    fn assert_topological(g: &Graph<i32>, order: &[String], edges: &[(&str, &str, i32)]) {
+        assert_eq!(order.len(), g.vertex_count());
+        let position = |id: &str| order.iter().position(|x| x == id).unwrap();
+        for (src, dst, _) in edges {
+            assert!(position(src) < position(dst), "{} should come before {}", src, dst);
+        }
+    }
+
+    // consecutive vertices, and the last back to the first, are joined by edges
+    fn assert_cycle(g: &Graph<i32>, cycle: &[String]) {
+        assert!(!cycle.is_empty());
+        for (i, id) in cycle.iter().enumerate() {
+            assert!(g.has_edge(id, &cycle[(i + 1) % cycle.len()]));
+        }
+    }
+
+    const BUILD: [(&str, &str, i32); 7] = [
+        ("core", "io", 1),
+        ("core", "net", 1),
+        ("io", "fs", 1),
+        ("net", "http", 1),
+        ("fs", "app", 1),
+        ("http", "app", 1),
+        ("log", "app", 1),
+    ];
+
+    #[test]
+    fn kahn_order() {
+        let g = graph_from(true, &["app", "core", "fs", "http", "io", "log", "net", "docs"], &BUILD);
+        let order = g.topological_sort().unwrap();
+        assert_topological(&g, &order, &BUILD);
+        // ties are broken on the order the vertices were added
+        assert_eq!(order, ["core", "io", "fs", "log", "net", "http", "app", "docs"]);
+        assert!(g.is_acyclic());
+    }
+
+    #[test]
+    fn dfs_order() {
+        let g = graph_from(true, &["app", "core", "fs", "http", "io", "log", "net", "docs"], &BUILD);
+        let order = g.topological_sort_dfs().unwrap();
+        assert_topological(&g, &order, &BUILD);
+    }
+
+    #[test]
+    fn cycle_detection() {
+        let edges = [("a", "b", 1), ("b", "c", 1), ("c", "d", 1), ("d", "b", 1), ("x", "a", 1)];
+        let g = graph_from(true, &["a", "b", "c", "d", "x"], &edges);
+
+        assert!(!g.is_acyclic());
+        let err = g.topological_sort().unwrap_err();
+        assert_eq!(err.cycle().len(), 3);
+        assert_cycle(&g, err.cycle());
+
+        let err = g.topological_sort_dfs().unwrap_err();
+        assert_eq!(err.cycle(), ["b", "c", "d"]);
+        assert_eq!(format!("{}", err), "cycle b->c->d->b");
+    }
+
+    #[test]
+    fn self_loop() {
+        let g = graph_from(true, &["a", "b"], &[("a", "b", 1), ("b", "b", 1)]);
+        assert!(!g.is_acyclic());
+        assert_eq!(g.topological_sort().unwrap_err().cycle(), ["b"]);
+    }
+
+    #[test]
+    fn empty_graph() {
+        let g = graph_from(true, &[], &[]);
+        assert!(g.is_acyclic());
+        assert_eq!(g.topological_sort().unwrap().len(), 0);
+    }
+}
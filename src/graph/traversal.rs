@@ -0,0 +1,388 @@
+use super::{GraphView, VertexId};
+use std::collections::VecDeque;
+
+// what a Visitor wants the traversal to do after one of its hooks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    Continue,
+    // from discover, don't follow the edges out of the vertex. from tree_edge, don't discover the destination
+    // through this edge. has no effect on the other hooks
+    Prune,
+    // end the traversal straight away
+    Stop,
+}
+
+// hooks called by GraphView::bfs, dfs and dfs_all as they walk the graph. every hook defaults to Continue,
+// so a visitor only implements the ones it cares about.
+// a vertex is discovered when it is first reached and finished once all of its out edges have been looked at.
+// each edge to a vertex that was not discovered yet is a tree edge. in a depth first search an edge to a vertex
+// that is not finished is a back edge, and closes a cycle. any other edge is a cross edge, including the forward
+// edges of a depth first search and every non tree edge of a breadth first search.
+// in an undirected graph every edge is reported once, and the tree edge back to the parent is skipped
+pub trait Visitor<W> {
+    fn discover(&mut self, _v: VertexId) -> Control {
+        Control::Continue
+    }
+
+    fn finish(&mut self, _v: VertexId) -> Control {
+        Control::Continue
+    }
+
+    fn tree_edge(&mut self, _src: VertexId, _dst: VertexId, _weight: W) -> Control {
+        Control::Continue
+    }
+
+    fn back_edge(&mut self, _src: VertexId, _dst: VertexId, _weight: W) -> Control {
+        Control::Continue
+    }
+
+    fn cross_edge(&mut self, _src: VertexId, _dst: VertexId, _weight: W) -> Control {
+        Control::Continue
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mark {
+    Open,   // discovered but not finished
+    Closed, // finished
+}
+
+// breadth first search from src. returns true if the visitor stopped it
+pub(super) fn bfs<G, V>(g: &G, src: VertexId, visitor: &mut V) -> bool
+where
+    G: GraphView + ?Sized,
+    V: Visitor<G::Weight>,
+{
+    if g.vertex_key(src).is_none() {
+        return false;
+    }
+
+    let mut marks: Vec<Option<Mark>> = vec![None; g.id_bound()];
+    let mut pruned = vec![false; g.id_bound()];
+    let mut queue: VecDeque<VertexId> = VecDeque::from([src]);
+    marks[src.index()] = Some(Mark::Open);
+    match visitor.discover(src) {
+        Control::Stop => return true,
+        Control::Prune => pruned[src.index()] = true,
+        Control::Continue => {}
+    }
+
+    while let Some(u) = queue.pop_front() {
+        // a pruned vertex is still finished in turn, its edges are just not looked at
+        let edges = (!pruned[u.index()]).then(|| g.out_edges(u));
+        for (v, w) in edges.into_iter().flatten() {
+            let control = match marks[v.index()] {
+                None => match visitor.tree_edge(u, v, w) {
+                    Control::Continue => {
+                        marks[v.index()] = Some(Mark::Open);
+                        queue.push_back(v);
+                        let control = visitor.discover(v);
+                        pruned[v.index()] = control == Control::Prune;
+                        control
+                    }
+                    control => control,
+                },
+                // an undirected edge to a finished vertex was already reported from that vertex
+                Some(Mark::Closed) if !g.is_directed() => Control::Continue,
+                Some(_) => visitor.cross_edge(u, v, w),
+            };
+            if control == Control::Stop {
+                return true;
+            }
+        }
+
+        marks[u.index()] = Some(Mark::Closed);
+        if visitor.finish(u) == Control::Stop {
+            return true;
+        }
+    }
+    false
+}
+
+// depth first search from each of roots in turn, skipping roots that are missing or were reached from an earlier root.
+// iterative, so deep graphs can't overflow the call stack. returns true if the visitor stopped it
+pub(super) fn dfs<G, V, I>(g: &G, roots: I, visitor: &mut V) -> bool
+where
+    G: GraphView + ?Sized,
+    V: Visitor<G::Weight>,
+    I: IntoIterator<Item = VertexId>,
+{
+    let mut marks: Vec<Option<Mark>> = vec![None; g.id_bound()];
+    // each entry is a vertex, the parent it was reached from if that tree edge hasn't been skipped yet, and its unseen out edges
+    let mut stack = Vec::new();
+
+    for root in roots {
+        if g.vertex_key(root).is_none() || marks[root.index()].is_some() {
+            continue;
+        }
+
+        let mut next = Some((root, None));
+        loop {
+            if let Some((v, parent)) = next.take() {
+                marks[v.index()] = Some(Mark::Open);
+                match visitor.discover(v) {
+                    Control::Stop => return true,
+                    Control::Prune => {
+                        marks[v.index()] = Some(Mark::Closed);
+                        if visitor.finish(v) == Control::Stop {
+                            return true;
+                        }
+                    }
+                    Control::Continue => stack.push((v, parent, g.out_edges(v))),
+                }
+            }
+
+            let Some((u, parent, edges)) = stack.last_mut() else {
+                break;
+            };
+            let u = *u;
+            match edges.next() {
+                Some((v, w)) => {
+                    let control = match marks[v.index()] {
+                        // the tree edge back to the parent of an undirected edge is the edge we came in on
+                        _ if !g.is_directed() && *parent == Some(v) => {
+                            *parent = None;
+                            Control::Continue
+                        }
+                        None => {
+                            let control = visitor.tree_edge(u, v, w);
+                            if control == Control::Continue {
+                                next = Some((v, Some(u)));
+                            }
+                            control
+                        }
+                        Some(Mark::Open) => visitor.back_edge(u, v, w),
+                        // an undirected edge to a finished vertex was already reported from that vertex as a back edge
+                        Some(Mark::Closed) if !g.is_directed() => Control::Continue,
+                        Some(Mark::Closed) => visitor.cross_edge(u, v, w),
+                    };
+                    if control == Control::Stop {
+                        return true;
+                    }
+                }
+                None => {
+                    stack.pop();
+                    marks[u.index()] = Some(Mark::Closed);
+                    if visitor.finish(u) == Control::Stop {
+                        return true;
+                    }
+                }
+            }
+        }
+    }
+    false
+}
+
+// stops a breadth first search at the first vertex the goal accepts, remembering the tree edges on the way
+pub(super) struct Goal<F> {
+    is_goal: F,
+    prev: Vec<Option<VertexId>>,
+    found: Option<VertexId>,
+}
+
+impl<F: Fn(VertexId) -> bool> Goal<F> {
+    pub(super) fn new(slots: usize, is_goal: F) -> Self {
+        Goal {
+            is_goal,
+            prev: vec![None; slots],
+            found: None,
+        }
+    }
+
+    // vertices from the search source to the goal, if one was found
+    pub(super) fn path(&self) -> Option<Vec<VertexId>> {
+        let mut path = vec![self.found?];
+        while let Some(v) = self.prev[path.last().unwrap().index()] {
+            path.push(v);
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+impl<F: Fn(VertexId) -> bool, W> Visitor<W> for Goal<F> {
+    fn discover(&mut self, v: VertexId) -> Control {
+        if (self.is_goal)(v) {
+            self.found = Some(v);
+            return Control::Stop;
+        }
+        Control::Continue
+    }
+
+    fn tree_edge(&mut self, src: VertexId, dst: VertexId, _weight: W) -> Control {
+        self.prev[dst.index()] = Some(src);
+        Control::Continue
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Control, Visitor};
+    use crate::graph::test_support::graph_from;
+    use crate::graph::{FrozenGraph, GraphView, VertexId};
+
+    // logs every hook as a string of keys. vertices named in prune or stop get that answer from discover
+    struct Recorder<'a> {
+        g: &'a FrozenGraph<i32>,
+        events: Vec<String>,
+        prune: &'a str,
+        stop: &'a str,
+    }
+
+    impl<'a> Recorder<'a> {
+        fn new(g: &'a FrozenGraph<i32>) -> Self {
+            Recorder {
+                g,
+                events: Vec::new(),
+                prune: "",
+                stop: "",
+            }
+        }
+
+        fn key(&self, v: VertexId) -> &str {
+            self.g.vertex_key(v).unwrap()
+        }
+
+        fn edge(&mut self, kind: &str, src: VertexId, dst: VertexId) -> Control {
+            let event = format!("{} {}{}", kind, self.key(src), self.key(dst));
+            self.events.push(event);
+            Control::Continue
+        }
+    }
+
+    impl Visitor<i32> for Recorder<'_> {
+        fn discover(&mut self, v: VertexId) -> Control {
+            let key = self.key(v).to_string();
+            self.events.push(format!("discover {}", key));
+            match key {
+                _ if key == self.prune => Control::Prune,
+                _ if key == self.stop => Control::Stop,
+                _ => Control::Continue,
+            }
+        }
+
+        fn finish(&mut self, v: VertexId) -> Control {
+            let event = format!("finish {}", self.key(v));
+            self.events.push(event);
+            Control::Continue
+        }
+
+        fn tree_edge(&mut self, src: VertexId, dst: VertexId, _weight: i32) -> Control {
+            self.edge("tree", src, dst)
+        }
+
+        fn back_edge(&mut self, src: VertexId, dst: VertexId, _weight: i32) -> Control {
+            self.edge("back", src, dst)
+        }
+
+        fn cross_edge(&mut self, src: VertexId, dst: VertexId, _weight: i32) -> Control {
+            self.edge("cross", src, dst)
+        }
+    }
+
+    // the graphs are frozen so the out edges of each vertex come in the order the vertices were added
+    fn id(g: &FrozenGraph<i32>, key: &str) -> VertexId {
+        g.vertex_id(&key.to_string()).unwrap()
+    }
+
+    #[test]
+    fn dfs_classifies_edges() {
+        let g = graph_from(true, &["a", "b", "c", "d"], &[("a", "b", 1), ("a", "c", 1), ("b", "c", 1), ("c", "a", 1), ("d", "b", 1)]).freeze();
+        let mut recorder = Recorder::new(&g);
+        assert!(!g.dfs_all(&mut recorder));
+        assert_eq!(
+            recorder.events,
+            [
+                "discover a",
+                "tree ab",
+                "discover b",
+                "tree bc",
+                "discover c",
+                "back ca",
+                "finish c",
+                "finish b",
+                "cross ac",
+                "finish a",
+                "discover d",
+                "cross db",
+                "finish d"
+            ]
+        );
+
+        // a search from one vertex only sees what it can reach
+        let mut recorder = Recorder::new(&g);
+        g.dfs(id(&g, "c"), &mut recorder);
+        assert!(!recorder.events.contains(&"discover d".to_string()));
+    }
+
+    #[test]
+    fn dfs_prune_and_stop() {
+        let g = graph_from(true, &["a", "b", "c", "d"], &[("a", "b", 1), ("b", "c", 1), ("a", "d", 1)]).freeze();
+        let mut recorder = Recorder::new(&g);
+        recorder.prune = "b";
+        assert!(!g.dfs(id(&g, "a"), &mut recorder));
+        assert_eq!(
+            recorder.events,
+            ["discover a", "tree ab", "discover b", "finish b", "tree ad", "discover d", "finish d", "finish a"]
+        );
+
+        let mut recorder = Recorder::new(&g);
+        recorder.stop = "c";
+        assert!(g.dfs(id(&g, "a"), &mut recorder));
+        assert_eq!(recorder.events.last().unwrap(), "discover c");
+    }
+
+    #[test]
+    fn bfs_visits_by_level() {
+        let g = graph_from(
+            true,
+            &["a", "b", "c", "d", "e"],
+            &[("a", "b", 1), ("a", "c", 1), ("b", "d", 1), ("c", "d", 1), ("d", "a", 1), ("c", "e", 1)],
+        )
+        .freeze();
+        let mut recorder = Recorder::new(&g);
+        assert!(!g.bfs(id(&g, "a"), &mut recorder));
+        let discovered: Vec<&str> = recorder.events.iter().filter_map(|e| e.strip_prefix("discover ")).collect();
+        assert_eq!(discovered, ["a", "b", "c", "d", "e"]);
+        assert!(recorder.events.contains(&"cross cd".to_string()));
+        assert!(recorder.events.contains(&"cross da".to_string()));
+
+        // pruning c keeps e out of reach, stopping at d ends before e is found
+        let mut recorder = Recorder::new(&g);
+        recorder.prune = "c";
+        g.bfs(id(&g, "a"), &mut recorder);
+        assert!(recorder.events.contains(&"finish c".to_string()));
+        assert!(!recorder.events.contains(&"discover e".to_string()));
+
+        let mut recorder = Recorder::new(&g);
+        recorder.stop = "d";
+        assert!(g.bfs(id(&g, "a"), &mut recorder));
+        assert_eq!(recorder.events.last().unwrap(), "discover d");
+    }
+
+    #[test]
+    fn undirected_edges_reported_once() {
+        let g = graph_from(false, &["a", "b", "c", "d"], &[("a", "b", 1), ("b", "c", 1), ("c", "a", 1), ("c", "d", 1)]).freeze();
+
+        let mut recorder = Recorder::new(&g);
+        g.dfs(id(&g, "a"), &mut recorder);
+        let edges: Vec<&String> = recorder.events.iter().filter(|e| !e.starts_with("discover") && !e.starts_with("finish")).collect();
+        assert_eq!(edges, ["tree ab", "tree bc", "back ca", "tree cd"]);
+
+        let mut recorder = Recorder::new(&g);
+        g.bfs(id(&g, "a"), &mut recorder);
+        let edges: Vec<&String> = recorder.events.iter().filter(|e| !e.starts_with("discover") && !e.starts_with("finish")).collect();
+        assert_eq!(edges, ["tree ab", "tree ac", "cross bc", "tree cd"]);
+    }
+
+    #[test]
+    fn search_by_payload() {
+        let g = graph_from(true, &["a", "b", "c", "d"], &[("a", "b", 1), ("b", "c", 1), ("a", "d", 1), ("d", "c", 1)]).freeze();
+        let a = "a".to_string();
+        assert_eq!(g.search_by(&a, |&value| value >= 2).unwrap(), ["a", "d"]);
+        assert_eq!(g.search_by(&a, |&value| value == 2).unwrap(), ["a", "b", "c"]);
+        assert_eq!(g.search_by(&a, |&value| value == 0).unwrap(), ["a"]);
+        assert_eq!(g.search_by(&"c".to_string(), |&value| value == 0), None);
+        assert_eq!(g.search_by(&"x".to_string(), |_| true), None);
+    }
+}
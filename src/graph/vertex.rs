@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+
+// compact handle for a vertex, see Graph::vertex_id. handles are assigned in the order vertices are added
+// and are never reused, so a handle to a removed vertex stays invalid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct VertexId(u32);
+
+impl VertexId {
+    pub(super) fn new(index: usize) -> Self {
+        VertexId(u32::try_from(index).expect("too many vertices for a VertexId"))
+    }
+
+    // position of the vertex in the graph's vertex storage
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub(super) struct Vertex<K, T> {
+    pub(super) id: K,
+    pub(super) value: T,
+}
+
+impl<K, T> fmt::Display for Vertex<K, T>
+where
+    K: fmt::Display,
+    T: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.id, self.value)
+    }
+}
+
+// interns vertex keys. maps each key to a VertexId, and each VertexId to the vertex with that key.
+// removed vertices leave an empty slot behind so the handles of the other vertices don't change
+#[derive(Debug, Clone)]
+pub(super) struct VertexTable<K, T> {
+    ids: HashMap<K, VertexId>,        // vertex key to handle
+    verts: Vec<Option<Vertex<K, T>>>, // handle to vertex, None if the vertex was removed
+}
+
+impl<K, T> VertexTable<K, T> {
+    pub(super) fn new() -> Self {
+        VertexTable {
+            ids: HashMap::new(),
+            verts: Vec::new(),
+        }
+    }
+
+    // number of vertices in the table
+    pub(super) fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    // one past the largest handle ever given out. per vertex data can be stored in a Vec of this length
+    pub(super) fn slots(&self) -> usize {
+        self.verts.len()
+    }
+
+    pub(super) fn get(&self, id: VertexId) -> Option<&Vertex<K, T>> {
+        self.verts.get(id.index())?.as_ref()
+    }
+
+    pub(super) fn get_mut(&mut self, id: VertexId) -> Option<&mut Vertex<K, T>> {
+        self.verts.get_mut(id.index())?.as_mut()
+    }
+
+    pub(super) fn contains(&self, id: VertexId) -> bool {
+        self.get(id).is_some()
+    }
+
+    // key of a vertex that is known to be in the table
+    pub(super) fn key(&self, id: VertexId) -> &K {
+        &self.get(id).unwrap().id
+    }
+
+    // every vertex with its handle, in the order they were added
+    pub(super) fn iter(&self) -> impl Iterator<Item = (VertexId, &Vertex<K, T>)> {
+        self.verts.iter().enumerate().filter_map(|(i, v)| Some((VertexId::new(i), v.as_ref()?)))
+    }
+
+    // handles of every vertex, in the order they were added
+    pub(super) fn ids(&self) -> impl Iterator<Item = VertexId> + '_ {
+        self.iter().map(|(id, _)| id)
+    }
+}
+
+impl<K, T> VertexTable<K, T>
+where
+    K: Hash + Eq + Clone,
+    T: Clone,
+{
+    // table with the vertices at the given handles, keeping the empty slots. None if two vertices share a key
+    pub(super) fn from_slots(verts: Vec<Option<Vertex<K, T>>>) -> Option<Self> {
+        let mut ids = HashMap::new();
+        for (i, vertex) in verts.iter().enumerate() {
+            if let Some(vertex) = vertex {
+                if ids.insert(vertex.id.clone(), VertexId::new(i)).is_some() {
+                    return None;
+                }
+            }
+        }
+        Some(VertexTable { ids, verts })
+    }
+
+    pub(super) fn id(&self, key: &K) -> Option<VertexId> {
+        self.ids.get(key).copied()
+    }
+
+    // adds a vertex if there is none with this key. returns the handle of the vertex with the key,
+    // and true if it was added
+    pub(super) fn insert(&mut self, key: &K, value: &T) -> (VertexId, bool) {
+        if let Some(&id) = self.ids.get(key) {
+            return (id, false);
+        }
+
+        let id = VertexId::new(self.verts.len());
+        self.verts.push(Some(Vertex {
+            id: key.clone(),
+            value: value.clone(),
+        }));
+        self.ids.insert(key.clone(), id);
+        (id, true)
+    }
+
+    pub(super) fn remove(&mut self, id: VertexId) -> Option<Vertex<K, T>> {
+        let vertex = self.verts.get_mut(id.index())?.take()?;
+        self.ids.remove(&vertex.id);
+        Some(vertex)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::VertexTable;
+
+    #[test]
+    fn intern_keys() {
+        let mut t: VertexTable<String, i32> = VertexTable::new();
+        let (a, added) = t.insert(&"a".to_string(), &1);
+        assert!(added);
+        let (b, _) = t.insert(&"b".to_string(), &2);
+        assert_eq!(t.insert(&"a".to_string(), &3), (a, false));
+        assert_eq!(t.get(a).unwrap().value, 1);
+        assert_eq!(t.id(&"b".to_string()), Some(b));
+        assert_eq!(t.key(b), "b");
+        assert_eq!(t.len(), 2);
+
+        // handles are not reused after a removal
+        assert_eq!(t.remove(a).unwrap().id, "a");
+        assert_eq!(t.remove(a), None);
+        assert!(!t.contains(a));
+        assert_eq!(t.id(&"a".to_string()), None);
+        let (c, _) = t.insert(&"a".to_string(), &4);
+        assert_ne!(a, c);
+        assert_eq!(t.len(), 2);
+        assert_eq!(t.slots(), 3);
+        assert_eq!(t.ids().collect::<Vec<_>>(), [b, c]);
+    }
+}
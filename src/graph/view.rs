@@ -0,0 +1,163 @@
+use super::shortest_path::{astar, bellman_ford, dijkstra_by};
+use super::traversal::{self, Goal};
+use super::{NegativeCycle, Path, ShortestPathTree, VertexId, Visitor, Weight};
+use std::hash::Hash;
+
+// read only access to a graph through VertexId handles. Graph and FrozenGraph both implement it,
+// and the traversal and shortest path queries below are written once against it.
+// only the first group of methods has to be implemented, the queries come for free
+pub trait GraphView {
+    type Key: Hash + Eq + Clone;
+    type Value;
+    type Weight: Weight;
+
+    fn is_directed(&self) -> bool;
+
+    fn vertex_count(&self) -> usize;
+
+    // number of edges, an undirected edge counts once
+    fn edge_count(&self) -> usize;
+
+    // one past the largest VertexId of the graph. searches keep their per vertex state in Vecs of this length
+    fn id_bound(&self) -> usize;
+
+    // handle of the vertex x
+    fn vertex_id(&self, id: &Self::Key) -> Option<VertexId>;
+
+    // key of the vertex with handle v
+    fn vertex_key(&self, v: VertexId) -> Option<&Self::Key>;
+
+    // the value of the vertex with handle v
+    fn value(&self, v: VertexId) -> Option<&Self::Value>;
+
+    // handles of every vertex
+    fn vertex_ids(&self) -> impl Iterator<Item = VertexId> + '_;
+
+    // every edge out of v with its weight. undirected edges are out edges of both of their vertices
+    fn out_edges(&self, v: VertexId) -> impl Iterator<Item = (VertexId, Self::Weight)> + '_;
+
+    // weight of the edge src->dst, if it exists
+    fn edge_weight_by_id(&self, src: VertexId, dst: VertexId) -> Option<Self::Weight> {
+        self.out_edges(src).find(|&(v, _)| v == dst).map(|(_, w)| w)
+    }
+
+    fn has_edge_by_id(&self, src: VertexId, dst: VertexId) -> bool {
+        self.edge_weight_by_id(src, dst).is_some()
+    }
+
+    // breadth first search from src, calling the hooks of visitor as it goes. see Visitor.
+    // returns true if the visitor stopped the search, false if it ran out of vertices or src is not in the graph
+    fn bfs<V: Visitor<Self::Weight>>(&self, src: VertexId, visitor: &mut V) -> bool {
+        traversal::bfs(self, src, visitor)
+    }
+
+    // depth first search from src, calling the hooks of visitor as it goes. see Visitor.
+    // returns true if the visitor stopped the search
+    fn dfs<V: Visitor<Self::Weight>>(&self, src: VertexId, visitor: &mut V) -> bool {
+        traversal::dfs(self, [src], visitor)
+    }
+
+    // depth first search of the whole graph, starting a new tree at each vertex that hasn't been discovered yet
+    // in VertexId order. returns true if the visitor stopped the search
+    fn dfs_all<V: Visitor<Self::Weight>>(&self, visitor: &mut V) -> bool {
+        traversal::dfs(self, self.vertex_ids(), visitor)
+    }
+
+    // breadth first search from src, returning dst if it can be reached
+    fn search(&self, src: &Self::Key, dst: &Self::Key) -> Option<Self::Key> {
+        let src = self.vertex_id(src)?;
+        let dst = self.vertex_id(dst)?;
+        let mut goal = Goal::new(self.id_bound(), |v| v == dst);
+        self.bfs(src, &mut goal);
+        goal.path().map(|_| key(self, dst))
+    }
+
+    // breadth first search from src for the nearest vertex whose value satisfies goal, src included.
+    // returns the keys of the path with the fewest edges from src to that vertex
+    fn search_by<P>(&self, src: &Self::Key, goal: P) -> Option<Vec<Self::Key>>
+    where
+        P: Fn(&Self::Value) -> bool,
+    {
+        let src = self.vertex_id(src)?;
+        let mut goal = Goal::new(self.id_bound(), |v| goal(self.value(v).unwrap()));
+        self.bfs(src, &mut goal);
+        Some(goal.path()?.into_iter().map(|v| key(self, v)).collect())
+    }
+
+    // shortest path between two vertices as a list of vertex keys, empty if there is no path.
+    // see find_path for the cost of the path and shortest_path_tree for the paths to every other vertex
+    fn shortest_path(&self, src: &Self::Key, dst: &Self::Key) -> Vec<Self::Key> {
+        self.find_path(src, dst).map(|path| path.vertices).unwrap_or_default()
+    }
+
+    fn shortest_path_by_id(&self, src: VertexId, dst: VertexId) -> Vec<VertexId> {
+        self.find_path_by_id(src, dst).map(|path| path.vertices).unwrap_or_default()
+    }
+
+    // dijkstra's algorithm with a binary heap. finds the shortest path from src to every reachable vertex.
+    // returns None if src is not in the graph.
+    // each vertex is settled once, so negative weights give wrong answers but never loop forever.
+    // vertices whose distance would overflow the weight type are treated as unreachable
+    fn shortest_path_tree(&self, src: &Self::Key) -> Option<ShortestPathTree<Self::Weight, Self::Key>> {
+        let src = self.vertex_id(src)?;
+        Some(dijkstra_by(self, src, |_, _, w| Some(w)).into_tree(src, |v| key(self, v)))
+    }
+
+    fn shortest_path_tree_by_id(&self, src: VertexId) -> Option<ShortestPathTree<Self::Weight, VertexId>> {
+        self.vertex_key(src)?;
+        Some(dijkstra_by(self, src, |_, _, w| Some(w)).into_tree(src, |v| v))
+    }
+
+    // bellman-ford algorithm. slower than shortest_path_tree at O(V*E) but gives correct distances when edges have negative weights.
+    // returns None if src is not in the graph, or a NegativeCycle error naming the vertices of one
+    // negative cycle that can be reached from src. negative cycles that can't be reached from src are ignored
+    #[allow(clippy::type_complexity)]
+    fn bellman_ford(&self, src: &Self::Key) -> Option<Result<ShortestPathTree<Self::Weight, Self::Key>, NegativeCycle<Self::Key>>> {
+        let src = self.vertex_id(src)?;
+        Some(match bellman_ford(self, src) {
+            Ok(search) => Ok(search.into_tree(src, |v| key(self, v))),
+            Err(cycle) => Err(cycle.map(|v| key(self, v))),
+        })
+    }
+
+    // shortest path from src to dst using dijkstra's algorithm, stopping as soon as dst is settled.
+    // returns None if either vertex is missing or dst can't be reached from src
+    fn find_path(&self, src: &Self::Key, dst: &Self::Key) -> Option<Path<Self::Weight, Self::Key>> {
+        self.astar(src, dst, |_, _| Self::Weight::zero())
+    }
+
+    fn find_path_by_id(&self, src: VertexId, dst: VertexId) -> Option<Path<Self::Weight, VertexId>> {
+        self.vertex_key(src)?;
+        self.vertex_key(dst)?;
+        astar(self, src, dst, |_| Self::Weight::zero())
+    }
+
+    // a* search from src to dst. heuristic(x, goal) is given the payloads of a vertex and of dst and estimates
+    // the remaining cost from x to dst. the estimate must never overestimate and must be consistent,
+    // heuristic(x, goal) <= w(x, y) + heuristic(y, goal) for every edge x->y, for the returned path to be the shortest.
+    // returns None if either vertex is missing or dst can't be reached from src
+    fn astar<H>(&self, src: &Self::Key, dst: &Self::Key, heuristic: H) -> Option<Path<Self::Weight, Self::Key>>
+    where
+        H: Fn(&Self::Value, &Self::Value) -> Self::Weight,
+    {
+        let src = self.vertex_id(src)?;
+        let dst = self.vertex_id(dst)?;
+        let goal = self.value(dst)?;
+        let path = astar(self, src, dst, |v| heuristic(self.value(v).unwrap(), goal))?;
+        Some(Path {
+            vertices: path.vertices.into_iter().map(|v| key(self, v)).collect(),
+            cost: path.cost,
+            expanded: path.expanded,
+        })
+    }
+}
+
+// key of a vertex that is known to be in the graph
+pub(super) fn key<G: GraphView + ?Sized>(g: &G, v: VertexId) -> G::Key {
+    g.vertex_key(v).unwrap().clone()
+}
+
+// every edge as (src, dst, weight). undirected edges are given in both directions
+pub(super) fn arcs<G: GraphView + ?Sized>(g: &G) -> impl Iterator<Item = (VertexId, VertexId, G::Weight)> + '_ {
+    g.vertex_ids().flat_map(move |v| g.out_edges(v).map(move |(u, w)| (v, u, w)))
+}
@@ -0,0 +1,136 @@
+use std::cmp::Ordering;
+
+// edge weight of a Graph. shortest path algorithms need a zero distance, a way to add distances together
+// without overflowing, a way to compare them, and an "infinity" that is greater than any distance that can be reached.
+// implemented for the primitive integer and float types. NaN weights are not supported
+pub trait Weight: Copy + PartialOrd {
+    // distance from a vertex to itself
+    fn zero() -> Self;
+
+    // weight given to edges added without one, see Graph::add_edge
+    fn one() -> Self;
+
+    // sentinel for "no path". compares greater than every finite weight. it is reserved, so an edge weighing
+    // infinity (u8::MAX for a u8 weight) is treated as missing by the shortest path searches
+    fn infinity() -> Self;
+
+    // self + rhs, or None if the sum can not be represented
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+
+    // self - rhs, or None if the difference can not be represented
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+
+    // self + rhs, clamped to infinity if either side is infinite or the sum overflows upwards
+    fn saturating_add(self, rhs: Self) -> Self;
+
+    fn is_infinity(self) -> bool {
+        self == Self::infinity()
+    }
+
+    // total order used by the priority queues. incomparable values (NaN) are treated as equal
+    fn weight_cmp(&self, rhs: &Self) -> Ordering {
+        self.partial_cmp(rhs).unwrap_or(Ordering::Equal)
+    }
+}
+
+macro_rules! impl_weight_int {
+    ($($t:ty),*) => {
+        $(
+            impl Weight for $t {
+                fn zero() -> Self {
+                    0
+                }
+                fn one() -> Self {
+                    1
+                }
+                fn infinity() -> Self {
+                    <$t>::MAX
+                }
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+                fn saturating_add(self, rhs: Self) -> Self {
+                    if self.is_infinity() || rhs.is_infinity() {
+                        return Self::infinity();
+                    }
+                    <$t>::saturating_add(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_weight_float {
+    ($($t:ty),*) => {
+        $(
+            impl Weight for $t {
+                fn zero() -> Self {
+                    0.0
+                }
+                fn one() -> Self {
+                    1.0
+                }
+                fn infinity() -> Self {
+                    <$t>::INFINITY
+                }
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    let sum = self + rhs;
+                    if sum.is_nan() {
+                        None
+                    } else {
+                        Some(sum)
+                    }
+                }
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    let difference = self - rhs;
+                    if difference.is_nan() {
+                        None
+                    } else {
+                        Some(difference)
+                    }
+                }
+                fn saturating_add(self, rhs: Self) -> Self {
+                    self + rhs
+                }
+            }
+        )*
+    };
+}
+
+impl_weight_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_weight_float!(f32, f64);
+
+#[cfg(test)]
+mod test {
+    use super::Weight;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn int_weights() {
+        assert_eq!(<i32 as Weight>::zero(), 0);
+        assert_eq!(<u64 as Weight>::infinity(), u64::MAX);
+        assert_eq!(Weight::checked_add(i32::MAX - 1, 1), Some(i32::MAX));
+        assert_eq!(Weight::checked_add(i32::MAX, 1), None);
+        assert_eq!(Weight::checked_sub(i32::MAX, -1), None);
+        assert_eq!(Weight::checked_sub(0u8, 1), None);
+        assert_eq!(Weight::checked_sub(-5i8, 3), Some(-8));
+        assert_eq!(Weight::saturating_add(250u8, 10), u8::MAX);
+        assert_eq!(Weight::saturating_add(i32::MAX, -5), i32::MAX);
+        assert_eq!(Weight::saturating_add(i32::MIN, -5), i32::MIN);
+        assert!(Weight::is_infinity(u8::MAX));
+    }
+
+    #[test]
+    fn float_weights() {
+        assert_eq!(<f64 as Weight>::one(), 1.0);
+        assert_eq!(Weight::checked_add(1.5f64, 2.0), Some(3.5));
+        assert_eq!(Weight::checked_add(f64::INFINITY, f64::NEG_INFINITY), None);
+        assert_eq!(Weight::checked_sub(f64::INFINITY, f64::INFINITY), None);
+        assert!(Weight::saturating_add(f64::INFINITY, -5.0).is_infinite());
+        assert_eq!(Weight::weight_cmp(&1.0f32, &2.0), Ordering::Less);
+        assert_eq!(Weight::weight_cmp(&f32::NAN, &2.0), Ordering::Equal);
+    }
+}
@@ -1,3 +1,4 @@
+pub mod disjoint_set;
 pub mod graph;
 pub mod mat4x4f;
 pub mod rand;